@@ -3,8 +3,7 @@ use std::env;
 
 /// Display annual development of enterprice value of given stock
 async fn show_financials(ticker: &str, gf_connect: &gfapi::GuruFocusConnector) {
-    let financials = gf_connect.get_financials(ticker).await.unwrap();
-    let financials: gfapi::FinancialData = serde_json::from_value(financials).unwrap();
+    let financials = gf_connect.financials(ticker).await.unwrap();
     println!(
         "Financial figures of {}'s Enterprice value\n==================\n{:#?}\n\n",
         ticker, financials
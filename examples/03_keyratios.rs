@@ -8,9 +8,7 @@ async fn main() {
 
     // Get key ratios of Berkshire Hathaway
     let stock = "NYSE:BRK.A";
-    let key_ratios = gf_connect.get_key_ratios(stock).await.unwrap();
-
-    let key_ratios: gfapi::KeyRatios = serde_json::from_value(key_ratios).unwrap();
+    let key_ratios = gf_connect.key_ratios(stock).await.unwrap();
     println!(
         "List of key ratios for Berkshire Hathaway\n{:#?}",
         key_ratios
@@ -1,5 +1,4 @@
 use gurufocus_api as gfapi;
-use std::collections::HashMap;
 use std::env;
 
 #[tokio::main]
@@ -8,8 +7,6 @@ async fn main() {
     let gf_connect = gfapi::GuruFocusConnector::new(token);
 
     let stock = "WMT";
-    let trades = gf_connect.get_guru_trades(stock).await.unwrap();
-
-    let trades: HashMap<String, gfapi::gurus::GuruTrades> = serde_json::from_value(trades).unwrap();
+    let trades = gf_connect.guru_trades(stock).await.unwrap();
     println!("List of real time guru trades in Walmart\n{:#?}", trades);
 }
@@ -50,7 +50,7 @@ async fn main() {
     let three_months_ago = month_before(now, 3);
     let page = 1;
     let trades = gf_connect
-        .get_guru_picks(&gurus, three_months_ago, page)
+        .guru_picks(&gurus, three_months_ago, page)
         .await
         .unwrap();
 
@@ -1,5 +1,4 @@
 use gurufocus_api as gfapi;
-use std::collections::HashMap;
 use std::env;
 
 #[tokio::main]
@@ -9,10 +8,7 @@ async fn main() {
 
     // Bill Ackman and David Einhorn
     let gurus = ["47", "39"];
-    let portfolios = gf_connect.get_guru_portfolios(&gurus).await.unwrap();
-
-    let portfolios: HashMap<String, gfapi::gurus::GuruPortfolio> =
-        serde_json::from_value(portfolios).unwrap();
+    let portfolios = gf_connect.guru_portfolios(&gurus).await.unwrap();
     println!(
         "Aggregated Portfolios of Bill Ackman and David Einhorn\n{:#?}",
         portfolios
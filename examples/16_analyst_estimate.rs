@@ -7,8 +7,6 @@ async fn main() {
     let gf_connect = gfapi::GuruFocusConnector::new(token);
 
     let stock = "NAS:CSCO";
-    let estimates = gf_connect.get_analyst_estimate(stock).await.unwrap();
-
-    let estimates: gfapi::AnalystEstimates = serde_json::from_value(estimates).unwrap();
+    let estimates = gf_connect.analyst_estimate(stock).await.unwrap();
     println!("Analyst estimates on Cisco\n{:#?}", estimates);
 }
@@ -4,8 +4,6 @@ use chrono::{Datelike, NaiveDate, Utc};
 use gurufocus_api as gfapi;
 use std::env;
 
-type UpdatedStocks = Vec<String>;
-
 fn get_days_from_month(year: i32, month: u32) -> u32 {
     NaiveDate::from_ymd_opt(
         match month {
@@ -48,9 +46,7 @@ async fn main() {
 
     let now = Utc::now().naive_local().date();
     let one_months_ago = month_before(now, 6);
-    let stocks = gf_connect.get_updated_stocks(one_months_ago).await.unwrap();
-
-    let stocks: UpdatedStocks = serde_json::from_value(stocks).unwrap();
+    let stocks = gf_connect.updated_stocks(one_months_ago).await.unwrap();
     println!(
         "List of stocks with updated fundamental data since {}\n{:#?}",
         one_months_ago, stocks
@@ -5,8 +5,7 @@ use std::env;
 async fn main() {
     let token = env::var("GURUFOCUS_TOKEN").unwrap();
     let gf_connect = gfapi::GuruFocusConnector::new(token);
-    let politicians = gf_connect.get_politicians().await.unwrap();
-    let politicians: Vec<gfapi::gurus::Politician> = serde_json::from_value(politicians).unwrap();
+    let politicians = gf_connect.politicians().await.unwrap();
 
     // Print list of politicians
     println!("List of politicians:");
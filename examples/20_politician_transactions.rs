@@ -9,11 +9,9 @@ async fn main() {
     let asset_type = None;
 
     let transactions = gf_connect
-        .get_politician_transactions(page, asset_type)
+        .politician_transactions(page, asset_type)
         .await
         .unwrap();
-    let transactions: gfapi::gurus::PoliticianTransactionList =
-        serde_json::from_value(transactions).unwrap();
 
     println!("List of politician transactions");
     for t in transactions.data {
@@ -0,0 +1,11 @@
+use gurufocus_api as gfapi;
+use std::env;
+
+fn main() {
+    let token = env::var("GURUFOCUS_TOKEN").unwrap();
+    let gf_connect = gfapi::BlockingGuruFocusConnector::new(token);
+    let ticker = ["NAS:AAPL", "FRA:APC", "LTS:0JQ4"];
+    let prices = gf_connect.quotes(&ticker).unwrap();
+    println!("Compare latest quotes of Apple stock prices and three different exchanges:");
+    println!("{:#?}", prices);
+}
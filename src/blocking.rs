@@ -0,0 +1,269 @@
+//! Synchronous version of the `GuruFocusConnector` for callers without an async runtime.
+//! Each connector owns a small tokio runtime driving the requests of the underlying async
+//! connector; all clones share this runtime.
+//!
+//! The blocking methods must not be called from within an async context, since blocking
+//! the thread of an async executor panics.
+
+use futures::stream::{Stream, StreamExt};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::pin::Pin;
+use std::sync::Arc;
+use tokio::runtime::Runtime;
+
+use crate::gurus::{self, AssetType, GuruPick, PoliticianTransaction};
+use crate::{
+    AnalystEstimates, Dividend, DividendEvent, DividendSafety, FinancialData, GuruFocusConnector,
+    GuruFocusError, InsiderTrade, InsiderUpdate, KeyRatios, PageOptions, Portfolio, PriceSeries,
+    Quote, RequestStats, ResponseCache, ReverseDcf, SafetyThresholds, Stock, StockSummary, Symbol,
+    ValuationInputs,
+};
+
+/// Connector sending requests to GuruFocus and blocking until the response is available
+#[derive(Clone, Debug)]
+pub struct BlockingGuruFocusConnector {
+    inner: GuruFocusConnector,
+    runtime: Arc<Runtime>,
+}
+
+/// Generate blocking wrappers of the async connector methods with the same name and arguments
+macro_rules! blocking_requests {
+    ($($(#[$attr:meta])* fn $name:ident(&self $(, $arg:ident: $ty:ty)*) -> $ret:ty;)*) => {
+        $(
+            $(#[$attr])*
+            pub fn $name(&self $(, $arg: $ty)*) -> Result<$ret, GuruFocusError> {
+                self.runtime.block_on(self.inner.$name($($arg),*))
+            }
+        )*
+    };
+}
+
+impl BlockingGuruFocusConnector {
+    /// Create a blocking connector with default settings for the given user token
+    ///
+    /// # Panics
+    ///
+    /// Panics if the internal runtime can not be created.
+    pub fn new(token: String) -> BlockingGuruFocusConnector {
+        GuruFocusConnector::new(token).into()
+    }
+
+    /// Returns the underlying async connector
+    pub fn inner(&self) -> &GuruFocusConnector {
+        &self.inner
+    }
+
+    /// Returns the number of requests sent so far, see `GuruFocusConnector::request_stats`
+    pub fn request_stats(&self) -> RequestStats {
+        self.inner.request_stats()
+    }
+
+    /// Returns the response cache, if configured
+    pub fn cache(&self) -> Option<&ResponseCache> {
+        self.inner.cache()
+    }
+
+    blocking_requests! {
+        /// Returns the full history of financial data for stock symbol given as argument
+        fn get_financials(&self, stock: impl Into<Symbol>) -> Value;
+        /// Returns the current key statistic figures for stock symbol given as argument
+        fn get_key_ratios(&self, stock: impl Into<Symbol>) -> Value;
+        /// Returns the current quote data of a comma separated list of symbols given as argument
+        fn get_quotes(&self, stocks: &[impl Into<Symbol> + Clone]) -> Value;
+        /// Returns the history of (adjusted) quoted prices for symbol given as argument
+        fn get_price_hist(&self, stock: impl Into<Symbol>) -> Value;
+        /// Returns the history of (unadjusted) quoted prices for symbol given as argument
+        fn get_unadj_price_hist(&self, stock: impl Into<Symbol>) -> Value;
+        /// Returns companies current price, valuation rations and ranks for symbol given as argument
+        fn get_stock_summary(&self, stock: impl Into<Symbol>) -> Value;
+        /// Returns real-time guru trades and holding data for symbol given as argument
+        fn get_guru_trades(&self, stock: impl Into<Symbol>) -> Value;
+        /// Returns real-time insider trades for symbol given as argument
+        fn get_insider_trades(&self, stock: impl Into<Symbol>) -> Value;
+        /// Returns lists of all and personalized gurus
+        fn get_gurus(&self) -> Value;
+        /// Returns list of gurus stock picks using list of guru ids since a given start date.
+        fn get_guru_picks(&self, gurus: &[&str], start_date: chrono::NaiveDate, page: i32) -> Value;
+        /// Returns list of aggregated guru portfolios given a slice of guru ids
+        fn get_guru_portfolios(&self, gurus: &[&str]) -> Value;
+        /// Returns list of supported exchanges
+        fn get_exchanges(&self) -> Value;
+        /// Returns list of all stocks of a particular exchange
+        fn get_listed_stocks(&self, exchange: &str) -> Value;
+        /// Returns list of latest insider trades ordered by insider transctions time
+        fn get_insider_updates(&self) -> Value;
+        /// Returns 30 years dividend history data of a stock
+        fn get_dividend_history(&self, stock: impl Into<Symbol>) -> Value;
+        /// Returns analyst estimate data of a stock
+        fn get_analyst_estimate(&self, stock: impl Into<Symbol>) -> Value;
+        /// Returns list of personal portfolios
+        fn get_personal_portfolio(&self) -> Value;
+        /// Returns list of all stocks with updated fundamental data within a week of the given date
+        fn get_updated_stocks(&self, date: chrono::NaiveDate) -> Value;
+        /// Returns lists of politicians
+        fn get_politicians(&self) -> Value;
+        /// Returns list of latest politician transactions
+        fn get_politician_transactions(&self, page: u32, asset_type: Option<AssetType>) -> Value;
+
+        /// Returns the full history of financial data for stock symbol given as argument
+        fn financials(&self, stock: impl Into<Symbol>) -> FinancialData;
+        /// Returns the current key statistic figures for stock symbol given as argument
+        fn key_ratios(&self, stock: impl Into<Symbol>) -> KeyRatios;
+        /// Returns the current quote data of a list of symbols given as argument
+        fn quotes(&self, stocks: &[impl Into<Symbol> + Clone]) -> Vec<Quote>;
+        /// Returns the history of (adjusted) quoted prices for symbol given as argument
+        fn price_hist(&self, stock: impl Into<Symbol>) -> PriceSeries;
+        /// Returns the history of (unadjusted) quoted prices for symbol given as argument
+        fn unadj_price_hist(&self, stock: impl Into<Symbol>) -> PriceSeries;
+        /// Returns companies current price, valuation rations and ranks for symbol given as argument
+        fn stock_summary(&self, stock: impl Into<Symbol>) -> StockSummary;
+        /// Returns real-time guru trades and holding data for symbol given as argument
+        fn guru_trades(&self, stock: impl Into<Symbol>) -> HashMap<String, gurus::GuruTrades>;
+        /// Returns real-time insider trades for symbol given as argument
+        fn insider_trades(&self, stock: impl Into<Symbol>) -> HashMap<String, Vec<InsiderTrade>>;
+        /// Returns lists of all and personalized gurus
+        fn gurus(&self) -> gurus::Gurus;
+        /// Returns the stock picks per guru id using list of guru ids since a given start date.
+        fn guru_picks(&self, gurus: &[&str], start_date: chrono::NaiveDate, page: i32)
+            -> HashMap<String, gurus::GuruPicks>;
+        /// Returns aggregated guru portfolios per guru id given a slice of guru ids
+        fn guru_portfolios(&self, gurus: &[&str]) -> HashMap<String, gurus::GuruPortfolio>;
+        /// Returns list of supported exchanges per country
+        fn exchanges(&self) -> HashMap<String, Vec<String>>;
+        /// Returns list of all stocks of a particular exchange
+        fn listed_stocks(&self, exchange: &str) -> Vec<Stock>;
+        /// Returns list of latest insider trades ordered by insider transctions time
+        fn insider_updates(&self) -> Vec<InsiderUpdate>;
+        /// Returns 30 years dividend history data of a stock
+        fn dividend_history(&self, stock: impl Into<Symbol>) -> Vec<Dividend>;
+        /// Returns the calendar of ex-dates and pay dates on or after `from` of the given symbols
+        fn dividend_calendar(&self, stocks: &[impl Into<Symbol> + Clone], from: chrono::NaiveDate)
+            -> Vec<DividendEvent>;
+        /// Returns the dividend sustainability reports of the given symbols as of the given date
+        fn dividend_safety(&self, stocks: &[impl Into<Symbol> + Clone], as_of: chrono::NaiveDate,
+            thresholds: &SafetyThresholds) -> Vec<DividendSafety>;
+        /// Returns analyst estimate data of a stock
+        fn analyst_estimate(&self, stock: impl Into<Symbol>) -> AnalystEstimates;
+        /// Returns the inputs of the valuation models for the symbol given as argument
+        fn valuation_inputs(&self, stock: impl Into<Symbol>) -> ValuationInputs;
+        /// Returns price and free cash flow per share of the symbol given as argument
+        fn reverse_dcf(&self, stock: impl Into<Symbol>) -> Option<ReverseDcf>;
+        /// Returns list of personal portfolios
+        fn personal_portfolio(&self) -> Vec<Portfolio>;
+        /// Returns list of all stocks with updated fundamental data within a week of the given date
+        fn updated_stocks(&self, date: chrono::NaiveDate) -> Vec<Symbol>;
+        /// Returns lists of politicians
+        fn politicians(&self) -> Vec<gurus::Politician>;
+        /// Returns a page of the list of latest politician transactions
+        fn politician_transactions(&self, page: u32, asset_type: Option<AssetType>)
+            -> gurus::PoliticianTransactionList;
+    }
+
+    /// Returns an iterator over all picks of the given gurus since `start_date` as pairs
+    /// of guru id and pick, see `GuruFocusConnector::guru_picks_stream`.
+    pub fn guru_picks_iter<'a>(
+        &'a self,
+        gurus: &'a [&'a str],
+        start_date: chrono::NaiveDate,
+        options: PageOptions,
+    ) -> impl Iterator<Item = Result<(String, GuruPick), GuruFocusError>> + 'a {
+        self.iter(self.inner.guru_picks_stream(gurus, start_date, options))
+    }
+
+    /// Returns an iterator over all politician transactions, optionally restricted to a
+    /// single asset type, see `GuruFocusConnector::politician_transactions_stream`.
+    pub fn politician_transactions_iter(
+        &self,
+        asset_type: Option<AssetType>,
+        options: PageOptions,
+    ) -> impl Iterator<Item = Result<PoliticianTransaction, GuruFocusError>> + '_ {
+        self.iter(
+            self.inner
+                .politician_transactions_stream(asset_type, options),
+        )
+    }
+
+    fn iter<'a, S: Stream + 'a>(&'a self, stream: S) -> BlockingIter<'a, S> {
+        BlockingIter {
+            runtime: &self.runtime,
+            stream: Box::pin(stream),
+        }
+    }
+}
+
+impl From<GuruFocusConnector> for BlockingGuruFocusConnector {
+    /// Wrap a configured async connector, e.g. one created via `GuruFocusConnector::builder`
+    ///
+    /// # Panics
+    ///
+    /// Panics if the internal runtime can not be created.
+    fn from(inner: GuruFocusConnector) -> BlockingGuruFocusConnector {
+        // a single worker thread suffices, since requests are driven by `block_on`, which
+        // may be called from several threads at once
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(1)
+            .thread_name("gurufocus-blocking")
+            .enable_all()
+            .build()
+            .expect("failed to create runtime of blocking GuruFocus connector");
+        BlockingGuruFocusConnector {
+            inner,
+            runtime: Arc::new(runtime),
+        }
+    }
+}
+
+/// Iterator blocking on each item of a stream
+struct BlockingIter<'a, S> {
+    runtime: &'a Runtime,
+    stream: Pin<Box<S>>,
+}
+
+impl<S: Stream> Iterator for BlockingIter<'_, S> {
+    type Item = S::Item;
+
+    fn next(&mut self) -> Option<S::Item> {
+        self.runtime.block_on(self.stream.next())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::replay::fixture_connector;
+
+    #[test]
+    fn blocking_requests() {
+        let gf_connect = BlockingGuruFocusConnector::from(fixture_connector());
+        let quotes = gf_connect.quotes(&["NAS:MSFT", "NAS:AAPL"]).unwrap();
+        assert_eq!(quotes[1].symbol, "AAPL");
+        let exchanges = gf_connect.get_exchanges().unwrap();
+        assert_eq!(exchanges["Norway"][0], "OSL");
+        assert!(gf_connect.get_gurus().is_ok());
+    }
+
+    #[test]
+    fn blocking_iterator() {
+        let gf_connect = BlockingGuruFocusConnector::from(fixture_connector());
+        let start = chrono::NaiveDate::from_ymd_opt(2023, 7, 1).unwrap();
+        let picks: Result<Vec<_>, _> = gf_connect
+            .guru_picks_iter(&["7", "16"], start, PageOptions::default())
+            .collect();
+        assert_eq!(picks.unwrap().len(), 2);
+    }
+
+    #[test]
+    fn shared_by_threads() {
+        let gf_connect = BlockingGuruFocusConnector::from(fixture_connector());
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let gf_connect = gf_connect.clone();
+                std::thread::spawn(move || gf_connect.politicians().unwrap().len())
+            })
+            .collect();
+        for handle in handles {
+            assert_eq!(handle.join().unwrap(), 2);
+        }
+    }
+}
@@ -0,0 +1,343 @@
+//! Optional on-disk cache of API responses. Each response is stored as JSON file in a
+//! sub-directory per endpoint, named after the request path (without the user token);
+//! names of long request paths are shortened and made unique by a hash of the path.
+//! Entries expire after a time-to-live configured per endpoint.
+
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use crate::Endpoint;
+
+const MINUTE: Duration = Duration::from_secs(60);
+const HOUR: Duration = Duration::from_secs(60 * 60);
+const DAY: Duration = Duration::from_secs(24 * 60 * 60);
+const WEEK: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// Maximum length of file names derived from request paths, leaving room for the extension
+/// of temporary files within the usual limit of 255 bytes
+const MAX_NAME_LEN: usize = 200;
+/// Length of the readable part of shortened file names
+const NAME_PREFIX_LEN: usize = 100;
+
+/// Usage of the cache by the connector
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CacheMode {
+    /// Use valid cache entries, send requests for missing or expired entries and
+    /// store the responses in the cache
+    ReadWrite,
+    /// Never send requests, but use all cache entries regardless of their age
+    Offline,
+}
+
+/// Cache storing responses as JSON files in a directory
+#[derive(Debug, Clone)]
+pub struct ResponseCache {
+    dir: PathBuf,
+    mode: CacheMode,
+    ttls: HashMap<Endpoint, Duration>,
+}
+
+/// Callback invoked with the request path and the error if a response could not be
+/// written to the cache
+type CacheErrorCallback = dyn Fn(&str, &io::Error) + Send + Sync;
+
+#[derive(Clone)]
+pub(crate) struct CacheErrorHandler(pub(crate) Arc<CacheErrorCallback>);
+
+impl fmt::Debug for CacheErrorHandler {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("CacheErrorHandler")
+    }
+}
+
+/// Default time-to-live of cached responses per endpoint
+fn default_ttl(endpoint: Endpoint) -> Duration {
+    match endpoint {
+        Endpoint::Quote => MINUTE,
+        Endpoint::InsiderUpdates => 10 * MINUTE,
+        Endpoint::Summary
+        | Endpoint::GuruTrades
+        | Endpoint::InsiderTrades
+        | Endpoint::GuruPicks
+        | Endpoint::FundamentalsUpdated
+        | Endpoint::PoliticianTransactions => HOUR,
+        Endpoint::Financials
+        | Endpoint::KeyRatios
+        | Endpoint::Price
+        | Endpoint::UnadjustedPrice
+        | Endpoint::GuruList
+        | Endpoint::GuruPortfolios
+        | Endpoint::ExchangeStocks
+        | Endpoint::Dividend
+        | Endpoint::AnalystEstimate => DAY,
+        Endpoint::ExchangeList | Endpoint::Politicians => WEEK,
+        // personal data is expected to be up to date
+        Endpoint::PersonalPortfolio => Duration::ZERO,
+    }
+}
+
+/// Turn request path into a valid file name. Path separators become `_`, other characters
+/// which may not be safe in file names (including `_` itself) are escaped as `%XX`, so that
+/// distinct paths get distinct names. Long paths, e.g. quotes of many symbols, are truncated
+/// and followed by a hash of the full path.
+pub(crate) fn file_name(path: &str) -> String {
+    let path = path.trim();
+    let mut name = String::with_capacity(path.len());
+    for c in path.chars() {
+        match c {
+            'a'..='z' | 'A'..='Z' | '0'..='9' | '-' | '.' => name.push(c),
+            '/' => name.push('_'),
+            _ => {
+                let mut buf = [0; 4];
+                for byte in c.encode_utf8(&mut buf).bytes() {
+                    name.push_str(&format!("%{:02X}", byte));
+                }
+            }
+        }
+    }
+    if name.len() > MAX_NAME_LEN {
+        // all characters are ASCII, so truncation keeps a valid string
+        name.truncate(NAME_PREFIX_LEN);
+        name = format!("{}_{:016x}", name, fnv1a(path.as_bytes()));
+    }
+    format!("{}.json", name)
+}
+
+/// 64-bit FNV-1a hash, which unlike the hasher of the standard library is guaranteed to
+/// be stable across Rust versions, so that cached files stay valid
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+impl ResponseCache {
+    /// Create a cache in the given directory using the default time-to-live per endpoint,
+    /// e.g. one minute for quotes, one day for financials and one week for the exchange list.
+    pub fn new<P: AsRef<Path>>(dir: P) -> ResponseCache {
+        ResponseCache {
+            dir: dir.as_ref().to_path_buf(),
+            mode: CacheMode::ReadWrite,
+            ttls: Endpoint::ALL
+                .iter()
+                .map(|endpoint| (*endpoint, default_ttl(*endpoint)))
+                .collect(),
+        }
+    }
+
+    /// Set time-to-live for responses of the given endpoint; zero disables caching
+    pub fn ttl(mut self, endpoint: Endpoint, ttl: Duration) -> Self {
+        self.ttls.insert(endpoint, ttl);
+        self
+    }
+
+    /// Set cache mode
+    pub fn mode(mut self, mode: CacheMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Returns the cache mode
+    pub fn cache_mode(&self) -> CacheMode {
+        self.mode
+    }
+
+    /// Directory holding the cached responses of an endpoint
+    fn endpoint_dir(&self, endpoint: Endpoint) -> PathBuf {
+        self.dir.join(endpoint.name())
+    }
+
+    fn entry_path(&self, endpoint: Endpoint, path: &str) -> PathBuf {
+        self.endpoint_dir(endpoint).join(file_name(path))
+    }
+
+    /// Returns the cached response for the request path, if available and not expired.
+    /// In offline mode, expired entries are returned as well.
+    pub(crate) fn get(&self, endpoint: Endpoint, path: &str) -> Option<Value> {
+        let file = self.entry_path(endpoint, path);
+        if self.mode == CacheMode::ReadWrite {
+            let ttl = self.ttls.get(&endpoint).copied().unwrap_or_default();
+            let age = fs::metadata(&file)
+                .and_then(|meta| meta.modified())
+                .ok()
+                .and_then(|modified| SystemTime::now().duration_since(modified).ok())?;
+            if age >= ttl {
+                return None;
+            }
+        }
+        let content = fs::read_to_string(file).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    /// Store response for the request path, unless caching is disabled for the endpoint
+    pub(crate) fn put(&self, endpoint: Endpoint, path: &str, value: &Value) -> io::Result<()> {
+        if self.ttls.get(&endpoint).copied().unwrap_or_default() == Duration::ZERO {
+            return Ok(());
+        }
+        let file = self.entry_path(endpoint, path);
+        fs::create_dir_all(self.endpoint_dir(endpoint))?;
+        // write to temporary file first to prevent readers seeing partial content
+        let tmp_file = file.with_extension("json.tmp");
+        fs::write(&tmp_file, value.to_string())?;
+        fs::rename(tmp_file, file)
+    }
+
+    /// Remove the cached response of a single request, e.g. `stock/AAPL/financials`
+    pub fn invalidate(&self, endpoint: Endpoint, path: &str) -> io::Result<()> {
+        ignore_not_found(fs::remove_file(self.entry_path(endpoint, path)))
+    }
+
+    /// Remove all cached responses of an endpoint
+    pub fn invalidate_endpoint(&self, endpoint: Endpoint) -> io::Result<()> {
+        ignore_not_found(fs::remove_dir_all(self.endpoint_dir(endpoint)))
+    }
+
+    /// Remove all cached responses
+    pub fn clear(&self) -> io::Result<()> {
+        for endpoint in Endpoint::ALL {
+            self.invalidate_endpoint(endpoint)?;
+        }
+        Ok(())
+    }
+}
+
+fn ignore_not_found(result: io::Result<()>) -> io::Result<()> {
+    match result {
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(()),
+        result => result,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn cache_entries_expire() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = ResponseCache::new(dir.path())
+            .ttl(Endpoint::Quote, Duration::ZERO)
+            .ttl(Endpoint::Financials, HOUR);
+        let value = json!({"financials": {}});
+        cache
+            .put(Endpoint::Financials, "stock/AAPL/financials", &value)
+            .unwrap();
+        cache
+            .put(Endpoint::Quote, "stock/AAPL/quote", &json!([]))
+            .unwrap();
+        assert_eq!(
+            cache.get(Endpoint::Financials, "stock/AAPL/financials"),
+            Some(value)
+        );
+        assert_eq!(
+            cache.get(Endpoint::Financials, "stock/MSFT/financials"),
+            None
+        );
+        assert_eq!(cache.get(Endpoint::Quote, "stock/AAPL/quote"), None);
+
+        let cache = cache.ttl(Endpoint::Financials, Duration::ZERO);
+        assert_eq!(
+            cache.get(Endpoint::Financials, "stock/AAPL/financials"),
+            None
+        );
+        let cache = cache.mode(CacheMode::Offline);
+        assert!(cache
+            .get(Endpoint::Financials, "stock/AAPL/financials")
+            .is_some());
+    }
+
+    #[test]
+    fn invalidate_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = ResponseCache::new(dir.path());
+        let value = json!([]);
+        cache
+            .put(Endpoint::Dividend, "stock/A/dividend", &value)
+            .unwrap();
+        cache
+            .put(Endpoint::Dividend, "stock/B/dividend", &value)
+            .unwrap();
+        cache
+            .put(Endpoint::ExchangeList, "exchange_list", &value)
+            .unwrap();
+
+        cache
+            .invalidate(Endpoint::Dividend, "stock/A/dividend")
+            .unwrap();
+        assert!(cache.get(Endpoint::Dividend, "stock/A/dividend").is_none());
+        assert!(cache.get(Endpoint::Dividend, "stock/B/dividend").is_some());
+        cache.invalidate_endpoint(Endpoint::Dividend).unwrap();
+        assert!(cache.get(Endpoint::Dividend, "stock/B/dividend").is_none());
+        assert!(cache.get(Endpoint::ExchangeList, "exchange_list").is_some());
+        cache.clear().unwrap();
+        assert!(cache.get(Endpoint::ExchangeList, "exchange_list").is_none());
+        // invalidating missing entries is no error
+        cache
+            .invalidate(Endpoint::Dividend, "stock/A/dividend")
+            .unwrap();
+    }
+
+    #[test]
+    fn file_names() {
+        assert_eq!(
+            file_name("stock/NAS:AAPL/quote"),
+            "stock_NAS%3AAAPL_quote.json"
+        );
+        assert_eq!(
+            file_name("politicians/transactions?page=2&asset_type=All"),
+            "politicians_transactions%3Fpage%3D2%26asset%5Ftype%3DAll.json"
+        );
+        assert_eq!(
+            file_name("funda_updated/2023-12-01"),
+            "funda%5Fupdated_2023-12-01.json"
+        );
+    }
+
+    #[test]
+    fn distinct_paths_get_distinct_file_names() {
+        let paths = [
+            "stock/NAS:AAPL/quote",
+            "stock/NAS_AAPL/quote",
+            "stock/NAS&AAPL/quote",
+            "stock/NAS=AAPL/quote",
+            "stock/NAS?AAPL/quote",
+            "stock_NAS/AAPL/quote",
+            "stock/NAS/AAPL/quote",
+        ];
+        let names: std::collections::HashSet<String> =
+            paths.iter().map(|path| file_name(path)).collect();
+        assert_eq!(names.len(), paths.len());
+    }
+
+    #[test]
+    fn long_request_paths() {
+        let quote_path = |exchange: &str| {
+            let symbols: Vec<String> = (0..100)
+                .map(|idx| format!("{}:SYM{}", exchange, idx))
+                .collect();
+            format!("stock/{}/quote", symbols.join(","))
+        };
+        let nas = file_name(&quote_path("NAS"));
+        let nys = file_name(&quote_path("NYSE"));
+        assert!(nas.starts_with("stock_NAS%3ASYM0%2CNAS%3ASYM1%2C"));
+        assert!(nas.len() + ".tmp".len() <= 255);
+        assert_ne!(nas, nys);
+        assert_eq!(nas, file_name(&quote_path("NAS")));
+
+        let dir = tempfile::tempdir().unwrap();
+        let cache = ResponseCache::new(dir.path()).ttl(Endpoint::Quote, HOUR);
+        let value = json!([{"symbol": "NAS:SYM0"}]);
+        cache
+            .put(Endpoint::Quote, &quote_path("NAS"), &value)
+            .unwrap();
+        assert_eq!(cache.get(Endpoint::Quote, &quote_path("NAS")), Some(value));
+        assert_eq!(cache.get(Endpoint::Quote, &quote_path("NYSE")), None);
+    }
+}
@@ -0,0 +1,300 @@
+//! Inference of corporate actions from the adjusted and unadjusted price history. GuruFocus
+//! adjusts historic prices for splits and dividends by multiplying them with an adjustment
+//! factor, which is the ratio of adjusted to unadjusted price. This factor is constant
+//! between two corporate actions and jumps at the first trading day after each action:
+//! by the split ratio for splits, and by `1 / (1 - dividend / previous close)` for cash
+//! dividends. Dividends inferred this way are cross-checked against the dividend history.
+
+use chrono::NaiveDate;
+
+use crate::prices::PriceSeries;
+use crate::stock::Dividend;
+
+/// Relative change of the adjustment factor below which prices are considered unchanged
+const FACTOR_TOLERANCE: f64 = 1e-4;
+/// Jumps of the adjustment factor of at least this size (or its inverse) are split candidates
+const MIN_SPLIT_JUMP: f64 = 1.2;
+/// Maximum relative deviation of a jump from a split ratio
+const SPLIT_RATIO_TOLERANCE: f64 = 0.005;
+/// Largest denominator of split ratios, e.g. 4 for a 5:4 split
+const MAX_SPLIT_DENOMINATOR: u32 = 4;
+/// Maximum relative deviation of an inferred dividend from the dividend history
+const DIVIDEND_TOLERANCE: f64 = 0.05;
+
+/// Corporate action inferred from the adjustment of historic prices
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CorporateAction {
+    /// Split effective at `date` with `ratio` new shares per old share, e.g. `4.0` for
+    /// a 4:1 split or `0.1` for a 1:10 reverse split
+    Split { date: NaiveDate, ratio: f64 },
+    /// Cash dividend with ex-date `date` and `amount` per unadjusted share; `factor` is the
+    /// price adjustment applied to all earlier prices, and `confirmed` tells whether a
+    /// matching entry has been found in the dividend history
+    Dividend {
+        date: NaiveDate,
+        amount: f64,
+        factor: f64,
+        confirmed: bool,
+    },
+    /// Adjustment explained neither by a split nor by a dividend, e.g. a spin-off;
+    /// `factor` is the price adjustment applied to all earlier prices
+    Unexplained { date: NaiveDate, factor: f64 },
+}
+
+impl CorporateAction {
+    /// Returns the first trading day affected by the action
+    pub fn date(&self) -> NaiveDate {
+        match self {
+            CorporateAction::Split { date, .. }
+            | CorporateAction::Dividend { date, .. }
+            | CorporateAction::Unexplained { date, .. } => *date,
+        }
+    }
+
+    /// Returns the factor applied to prices before the action to make them comparable
+    /// with later prices
+    pub fn price_factor(&self) -> f64 {
+        match self {
+            CorporateAction::Split { ratio, .. } => 1.0 / ratio,
+            CorporateAction::Dividend { factor, .. }
+            | CorporateAction::Unexplained { factor, .. } => *factor,
+        }
+    }
+}
+
+/// Infer corporate actions by comparing adjusted and unadjusted prices on all dates
+/// contained in both series. Inferred dividends are marked as confirmed if `dividends`
+/// contains an entry with the same ex-date and an amount matching either per unadjusted
+/// share or per share adjusted for later splits.
+pub fn detect_corporate_actions(
+    adjusted: &PriceSeries,
+    unadjusted: &PriceSeries,
+    dividends: &[Dividend],
+) -> Vec<CorporateAction> {
+    // adjustment factor per date together with the previous unadjusted close
+    let factors: Vec<(NaiveDate, f64, f64)> = adjusted
+        .iter()
+        .filter_map(|&(date, adj)| {
+            let unadj = unadjusted.get(date)?;
+            if unadj > 0.0 && adj > 0.0 {
+                Some((date, adj / unadj, unadj))
+            } else {
+                None
+            }
+        })
+        .collect();
+    let mut actions: Vec<CorporateAction> = factors
+        .windows(2)
+        .filter_map(|pair| {
+            let (_, previous_factor, previous_close) = pair[0];
+            let (date, factor, _) = pair[1];
+            let jump = factor / previous_factor;
+            if (jump - 1.0).abs() <= FACTOR_TOLERANCE {
+                return None;
+            }
+            if jump >= MIN_SPLIT_JUMP || jump <= 1.0 / MIN_SPLIT_JUMP {
+                if let Some(ratio) = split_ratio(jump) {
+                    return Some(CorporateAction::Split { date, ratio });
+                }
+            } else if jump > 1.0 {
+                return Some(CorporateAction::Dividend {
+                    date,
+                    amount: previous_close * (1.0 - 1.0 / jump),
+                    factor: 1.0 / jump,
+                    confirmed: false,
+                });
+            }
+            Some(CorporateAction::Unexplained {
+                date,
+                factor: 1.0 / jump,
+            })
+        })
+        .collect();
+
+    // dividend amounts may be given per share adjusted for all later splits
+    let splits: Vec<(NaiveDate, f64)> = actions
+        .iter()
+        .filter_map(|action| match action {
+            CorporateAction::Split { date, ratio } => Some((*date, *ratio)),
+            _ => None,
+        })
+        .collect();
+    for action in &mut actions {
+        if let CorporateAction::Dividend {
+            date,
+            amount,
+            confirmed,
+            ..
+        } = action
+        {
+            let later_splits: f64 = splits
+                .iter()
+                .filter(|(split_date, _)| split_date > date)
+                .map(|(_, ratio)| ratio)
+                .product();
+            *confirmed = dividends.iter().any(|dividend| {
+                let recorded = dividend.amount.value();
+                dividend.ex_date == *date
+                    && (is_close(recorded, *amount) || is_close(recorded * later_splits, *amount))
+            });
+        }
+    }
+    actions
+}
+
+/// Apply the given corporate actions to unadjusted prices, i.e. multiply each price with
+/// the price factors of all actions after its date
+pub fn adjust_prices(unadjusted: &PriceSeries, actions: &[CorporateAction]) -> PriceSeries {
+    unadjusted
+        .iter()
+        .map(|&(date, price)| {
+            let factor: f64 = actions
+                .iter()
+                .filter(|action| action.date() > date)
+                .map(CorporateAction::price_factor)
+                .product();
+            (date, price * factor)
+        })
+        .collect()
+}
+
+/// Convert a number of shares as of the given date into today's shares by applying all
+/// later splits, e.g. 100 shares before a 4:1 split become 400 shares
+pub fn adjust_shares(shares: f64, as_of: NaiveDate, actions: &[CorporateAction]) -> f64 {
+    actions
+        .iter()
+        .filter_map(|action| match action {
+            CorporateAction::Split { date, ratio } if *date > as_of => Some(ratio),
+            _ => None,
+        })
+        .fold(shares, |shares, ratio| shares * ratio)
+}
+
+/// Returns the split ratio (e.g. 3:2 or 1:10) matching the given jump, if any
+fn split_ratio(jump: f64) -> Option<f64> {
+    // reverse splits are matched by their inverse ratio, e.g. 10:1 for 1:10
+    let is_reverse = jump < 1.0;
+    let forward = if is_reverse { 1.0 / jump } else { jump };
+    (1..=MAX_SPLIT_DENOMINATOR).find_map(|denominator| {
+        let denominator = denominator as f64;
+        let numerator = (forward * denominator).round();
+        let ratio = numerator / denominator;
+        if !is_within(ratio, forward, SPLIT_RATIO_TOLERANCE) {
+            None
+        } else if is_reverse {
+            Some(denominator / numerator)
+        } else {
+            Some(ratio)
+        }
+    })
+}
+
+fn is_close(x: f64, y: f64) -> bool {
+    is_within(x, y, DIVIDEND_TOLERANCE)
+}
+
+fn is_within(x: f64, y: f64, tolerance: f64) -> bool {
+    (x - y).abs() <= tolerance * y.abs()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::replay::fixture_connector;
+    use crate::test_util::{assert_close, ymd};
+
+    #[tokio::test]
+    async fn actions_from_fixture() {
+        let gf_connect = fixture_connector();
+        let adjusted = gf_connect.price_hist("NAS:AAPL").await.unwrap();
+        let unadjusted = gf_connect.unadj_price_hist("NAS:AAPL").await.unwrap();
+        let dividends = gf_connect.dividend_history("NAS:AAPL").await.unwrap();
+        let actions = detect_corporate_actions(&adjusted, &unadjusted, &dividends);
+        assert_eq!(actions.len(), 2);
+        match actions[0] {
+            CorporateAction::Dividend {
+                date,
+                amount,
+                confirmed,
+                ..
+            } => {
+                assert_eq!(date, ymd(2020, 8, 7));
+                assert_close(amount, 0.82, 1e-3);
+                assert!(confirmed);
+            }
+            action => panic!("unexpected action {:?}", action),
+        }
+        assert_eq!(
+            actions[1],
+            CorporateAction::Split {
+                date: ymd(2020, 8, 31),
+                ratio: 4.0
+            }
+        );
+
+        let readjusted = adjust_prices(&unadjusted, &actions);
+        for (&(_, expected), &(_, price)) in adjusted.iter().zip(readjusted.iter()) {
+            assert_close(price / expected, 1.0, 1e-5);
+        }
+        assert_eq!(adjust_shares(100.0, ymd(2020, 8, 28), &actions), 400.0);
+        assert_eq!(adjust_shares(100.0, ymd(2020, 8, 31), &actions), 100.0);
+
+        // dividends not found in the history are reported as unconfirmed
+        let actions = detect_corporate_actions(&adjusted, &unadjusted, &[]);
+        assert!(matches!(
+            actions[0],
+            CorporateAction::Dividend {
+                confirmed: false,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn reverse_splits_and_spin_offs() {
+        let unadjusted: PriceSeries = vec![
+            (ymd(2023, 3, 1), 2.0),
+            (ymd(2023, 3, 2), 20.0),
+            (ymd(2023, 3, 3), 10.0),
+            (ymd(2023, 3, 6), 15.0),
+            (ymd(2023, 3, 7), 10.0),
+        ]
+        .into_iter()
+        .collect();
+        // 1:10 reverse split on 3/2, 3:2 split on 3/6 and spin-off worth 30% of the
+        // value on 3/7
+        let adjusted: PriceSeries = vec![
+            (ymd(2023, 3, 1), 20.0 / 1.5 * 0.7),
+            (ymd(2023, 3, 2), 20.0 / 1.5 * 0.7),
+            (ymd(2023, 3, 3), 10.0 / 1.5 * 0.7),
+            (ymd(2023, 3, 6), 15.0 * 0.7),
+            (ymd(2023, 3, 7), 10.0),
+        ]
+        .into_iter()
+        .collect();
+        let actions = detect_corporate_actions(&adjusted, &unadjusted, &[]);
+        assert_eq!(actions.len(), 3);
+        assert_eq!(
+            actions[0],
+            CorporateAction::Split {
+                date: ymd(2023, 3, 2),
+                ratio: 0.1
+            }
+        );
+        assert_eq!(
+            actions[1],
+            CorporateAction::Split {
+                date: ymd(2023, 3, 6),
+                ratio: 1.5
+            }
+        );
+        assert_eq!(actions[2].date(), ymd(2023, 3, 7));
+        assert!(matches!(actions[2], CorporateAction::Unexplained { .. }));
+        assert_close(actions[2].price_factor(), 0.7, 1e-9);
+        assert_close(
+            adjust_shares(1000.0, ymd(2023, 3, 1), &actions),
+            150.0,
+            1e-9,
+        );
+    }
+}
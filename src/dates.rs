@@ -0,0 +1,424 @@
+//! Tolerant deserialization of the dates returned by GuruFocus, which come in several
+//! formats: `YYYY-MM-DD`, `YYYY-MM-DD HH:MM:SS`, `MM-DD-YYYY`, months as `YYYY-MM` or
+//! `YYYYMM`, UNIX timestamps (as number or string) and empty strings for missing dates.
+//! Months are mapped to their last day, which is the convention for fiscal periods.
+//!
+//! The submodules are meant to be used via `#[serde(with = "...")]` on fields of type
+//! `NaiveDate`, `NaiveDateTime`, their `Option` or a `Vec` of `NaiveDate`. They serialize
+//! dates as `YYYY-MM-DD`, date and time as `YYYY-MM-DDTHH:MM:SS` and missing values as `null`,
+//! which are read back into the same values.
+
+use chrono::{DateTime, Datelike, NaiveDate, NaiveDateTime, NaiveTime};
+use serde::de::{self, Deserializer, SeqAccess, Visitor};
+use serde::ser::{SerializeSeq, Serializer};
+use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
+use std::fmt;
+use std::str::FromStr;
+
+/// Minimum number of digits of UNIX timestamps given as string, which excludes years like
+/// `2023` and other short numbers (9 digits cover all dates since March 1973)
+const MIN_TIMESTAMP_DIGITS: usize = 9;
+
+/// Parse a date given in any of the supported formats; returns `None` if the string is
+/// empty, a placeholder like `0000-00-00` or not a valid date
+pub fn parse_date(s: &str) -> Option<NaiveDate> {
+    parse_date_time(s).map(|dt| dt.date())
+}
+
+/// Parse a date and time given in any of the supported formats; dates without time are
+/// mapped to midnight
+pub fn parse_date_time(s: &str) -> Option<NaiveDateTime> {
+    let s = s.trim();
+    if s.chars().all(|c| c.is_ascii_digit()) {
+        return match s.len() {
+            0 => None,
+            6 => month_end(s[..4].parse().ok()?, s[4..].parse().ok()?).map(midnight),
+            8 => NaiveDate::parse_from_str(s, "%Y%m%d").ok().map(midnight),
+            len if len >= MIN_TIMESTAMP_DIGITS => from_timestamp(s.parse().ok()?),
+            _ => None,
+        };
+    }
+    for format in [
+        "%Y-%m-%d %H:%M:%S",
+        "%Y-%m-%dT%H:%M:%S%.f",
+        "%Y-%m-%d %H:%M",
+    ] {
+        if let Ok(dt) = NaiveDateTime::parse_from_str(s, format) {
+            return Some(dt);
+        }
+    }
+    if let Ok(dt) = DateTime::parse_from_rfc3339(s) {
+        return Some(dt.naive_utc());
+    }
+    for format in ["%Y-%m-%d", "%m-%d-%Y", "%m/%d/%Y", "%Y/%m/%d"] {
+        if let Ok(date) = NaiveDate::parse_from_str(s, format) {
+            return Some(midnight(date));
+        }
+    }
+    let (year, month) = s.split_once('-')?;
+    month_end(year.parse().ok()?, month.parse().ok()?).map(midnight)
+}
+
+/// Last day of the given month
+pub(crate) fn month_end(year: i32, month: u32) -> Option<NaiveDate> {
+    let (next_year, next_month) = if month == 12 {
+        (year + 1, 1)
+    } else {
+        (year, month + 1)
+    };
+    NaiveDate::from_ymd_opt(next_year, next_month, 1)?.pred_opt()
+}
+
+fn midnight(date: NaiveDate) -> NaiveDateTime {
+    date.and_time(NaiveTime::MIN)
+}
+
+/// Convert UNIX timestamp given in seconds or milliseconds
+fn from_timestamp(timestamp: i64) -> Option<NaiveDateTime> {
+    // timestamps in seconds beyond the year 5000 are assumed to be milliseconds
+    let seconds = if timestamp.abs() > 100_000_000_000 {
+        timestamp / 1000
+    } else {
+        timestamp
+    };
+    DateTime::from_timestamp(seconds, 0).map(|dt| dt.naive_utc())
+}
+
+/// Raw date representation found in the JSON response
+enum RawDate {
+    Text(String),
+    Timestamp(i64),
+    Missing,
+}
+
+impl RawDate {
+    fn to_date_time<E: de::Error>(&self) -> Result<Option<NaiveDateTime>, E> {
+        match self {
+            RawDate::Missing | RawDate::Timestamp(0) => Ok(None),
+            RawDate::Text(s) if is_placeholder(s) => Ok(None),
+            RawDate::Text(s) => parse_date_time(s)
+                .map(Some)
+                .ok_or_else(|| E::custom(format!("invalid date '{}'", s))),
+            RawDate::Timestamp(t) => from_timestamp(*t)
+                .map(Some)
+                .ok_or_else(|| E::custom(format!("invalid timestamp {}", t))),
+        }
+    }
+}
+
+/// Strings used by GuruFocus instead of a missing date
+fn is_placeholder(s: &str) -> bool {
+    matches!(
+        s.trim(),
+        "" | "-" | "N/A" | "0" | "0000-00-00" | "0000-00-00 00:00:00"
+    )
+}
+
+impl<'de> Deserialize<'de> for RawDate {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<RawDate, D::Error> {
+        struct RawDateVisitor;
+
+        impl<'de> Visitor<'de> for RawDateVisitor {
+            type Value = RawDate;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("date as string or timestamp")
+            }
+
+            fn visit_str<E: de::Error>(self, value: &str) -> Result<RawDate, E> {
+                Ok(RawDate::Text(value.to_string()))
+            }
+
+            fn visit_i64<E: de::Error>(self, value: i64) -> Result<RawDate, E> {
+                Ok(RawDate::Timestamp(value))
+            }
+
+            fn visit_u64<E: de::Error>(self, value: u64) -> Result<RawDate, E> {
+                i64::try_from(value)
+                    .map(RawDate::Timestamp)
+                    .map_err(|_| E::custom(format!("invalid timestamp {}", value)))
+            }
+
+            fn visit_f64<E: de::Error>(self, value: f64) -> Result<RawDate, E> {
+                Ok(RawDate::Timestamp(value as i64))
+            }
+
+            fn visit_unit<E: de::Error>(self) -> Result<RawDate, E> {
+                Ok(RawDate::Missing)
+            }
+
+            fn visit_none<E: de::Error>(self) -> Result<RawDate, E> {
+                Ok(RawDate::Missing)
+            }
+
+            fn visit_some<D: Deserializer<'de>>(self, d: D) -> Result<RawDate, D::Error> {
+                d.deserialize_any(self)
+            }
+        }
+
+        deserializer.deserialize_any(RawDateVisitor)
+    }
+}
+
+const DATE_FORMAT: &str = "%Y-%m-%d";
+const DATE_TIME_FORMAT: &str = "%Y-%m-%dT%H:%M:%S%.f";
+
+fn required<T, E: de::Error>(value: Option<T>) -> Result<T, E> {
+    value.ok_or_else(|| E::custom("missing date"))
+}
+
+/// Mandatory date
+pub mod date {
+    use super::*;
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<NaiveDate, D::Error> {
+        let dt = RawDate::deserialize(d)?.to_date_time()?;
+        required(dt.map(|dt| dt.date()))
+    }
+
+    pub fn serialize<S: Serializer>(date: &NaiveDate, s: S) -> Result<S::Ok, S::Error> {
+        s.collect_str(&date.format(DATE_FORMAT))
+    }
+}
+
+/// Optional date, empty strings and `null` are mapped to `None`
+pub mod option_date {
+    use super::*;
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Option<NaiveDate>, D::Error> {
+        Ok(RawDate::deserialize(d)?.to_date_time()?.map(|dt| dt.date()))
+    }
+
+    pub fn serialize<S: Serializer>(date: &Option<NaiveDate>, s: S) -> Result<S::Ok, S::Error> {
+        match date {
+            Some(date) => super::date::serialize(date, s),
+            None => s.serialize_none(),
+        }
+    }
+}
+
+/// Mandatory date and time
+pub mod date_time {
+    use super::*;
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<NaiveDateTime, D::Error> {
+        required(RawDate::deserialize(d)?.to_date_time()?)
+    }
+
+    pub fn serialize<S: Serializer>(dt: &NaiveDateTime, s: S) -> Result<S::Ok, S::Error> {
+        s.collect_str(&dt.format(DATE_TIME_FORMAT))
+    }
+}
+
+/// Optional date and time, empty strings and `null` are mapped to `None`
+pub mod option_date_time {
+    use super::*;
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Option<NaiveDateTime>, D::Error> {
+        RawDate::deserialize(d)?.to_date_time()
+    }
+
+    pub fn serialize<S: Serializer>(dt: &Option<NaiveDateTime>, s: S) -> Result<S::Ok, S::Error> {
+        match dt {
+            Some(dt) => super::date_time::serialize(dt, s),
+            None => s.serialize_none(),
+        }
+    }
+}
+
+/// List of mandatory dates
+pub mod date_list {
+    use super::*;
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Vec<NaiveDate>, D::Error> {
+        struct DateListVisitor;
+
+        impl<'de> Visitor<'de> for DateListVisitor {
+            type Value = Vec<NaiveDate>;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("list of dates")
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+                let mut dates = Vec::with_capacity(seq.size_hint().unwrap_or_default());
+                while let Some(raw) = seq.next_element::<RawDate>()? {
+                    dates.push(required(raw.to_date_time()?.map(|dt| dt.date()))?);
+                }
+                Ok(dates)
+            }
+        }
+
+        d.deserialize_seq(DateListVisitor)
+    }
+
+    pub fn serialize<S: Serializer>(dates: &[NaiveDate], s: S) -> Result<S::Ok, S::Error> {
+        let mut seq = s.serialize_seq(Some(dates.len()))?;
+        for date in dates {
+            seq.serialize_element(&date.format(DATE_FORMAT).to_string())?;
+        }
+        seq.end()
+    }
+}
+
+/// Column of the financial data, either a fiscal period identified by the last day of
+/// its last month, or the trailing twelve months
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum FiscalPeriod {
+    /// Fiscal year or quarter ending at the given date
+    Period(NaiveDate),
+    /// Trailing twelve months
+    Ttm,
+}
+
+impl FiscalPeriod {
+    /// Returns the end date of the period, or `None` for the trailing twelve months
+    pub fn end_date(&self) -> Option<NaiveDate> {
+        match self {
+            FiscalPeriod::Period(date) => Some(*date),
+            FiscalPeriod::Ttm => None,
+        }
+    }
+
+    /// Returns true for the trailing twelve months
+    pub fn is_ttm(&self) -> bool {
+        *self == FiscalPeriod::Ttm
+    }
+}
+
+impl fmt::Display for FiscalPeriod {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FiscalPeriod::Period(date) => write!(f, "{:04}-{:02}", date.year(), date.month()),
+            FiscalPeriod::Ttm => write!(f, "TTM"),
+        }
+    }
+}
+
+impl FromStr for FiscalPeriod {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<FiscalPeriod, String> {
+        if s.trim().eq_ignore_ascii_case("TTM") {
+            Ok(FiscalPeriod::Ttm)
+        } else {
+            parse_date(s)
+                .map(FiscalPeriod::Period)
+                .ok_or_else(|| format!("invalid fiscal period '{}'", s))
+        }
+    }
+}
+
+/// Serialized as `YYYY-MM` for periods ending at the end of a month, which is how they are
+/// given by GuruFocus, as `YYYY-MM-DD` otherwise and as `TTM` for the trailing twelve months
+impl Serialize for FiscalPeriod {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            FiscalPeriod::Period(date) if month_end(date.year(), date.month()) != Some(*date) => {
+                serializer.collect_str(&date.format(DATE_FORMAT))
+            }
+            _ => serializer.collect_str(self),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for FiscalPeriod {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<FiscalPeriod, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::ymd;
+    use serde_json::json;
+
+    #[test]
+    fn date_formats() {
+        let date = Some(ymd(2023, 6, 30));
+        assert_eq!(parse_date("2023-06-30"), date);
+        assert_eq!(parse_date("2023-06-30 16:00:00"), date);
+        assert_eq!(parse_date("06-30-2023"), date);
+        assert_eq!(parse_date("2023-06"), date);
+        assert_eq!(parse_date("202306"), date);
+        assert_eq!(parse_date("20230630"), date);
+        assert_eq!(parse_date("1688083200"), date);
+        assert_eq!(parse_date("1688083200000"), date);
+        assert_eq!(parse_date("2024-02"), Some(ymd(2024, 2, 29)));
+        assert_eq!(parse_date(""), None);
+        assert_eq!(parse_date("soon"), None);
+        assert_eq!(parse_date("2023"), None);
+        assert_eq!(parse_date("0"), None);
+        assert_eq!(
+            parse_date_time("2023-12-29 16:00:00"),
+            Some(ymd(2023, 12, 29).and_hms_opt(16, 0, 0).unwrap())
+        );
+    }
+
+    #[derive(Deserialize, Serialize, Debug, PartialEq)]
+    struct Dates {
+        #[serde(with = "date")]
+        date: NaiveDate,
+        #[serde(with = "option_date")]
+        missing: Option<NaiveDate>,
+        #[serde(with = "option_date_time")]
+        null: Option<NaiveDateTime>,
+        #[serde(with = "date_time")]
+        timestamp: NaiveDateTime,
+        #[serde(with = "date_list")]
+        months: Vec<NaiveDate>,
+    }
+
+    #[test]
+    fn deserialize_dates() {
+        let dates: Dates = serde_json::from_value(json!({
+            "date": "2023-01-31", "missing": "", "null": null, "timestamp": 1703865600,
+            "months": ["202406", "2025-06"]
+        }))
+        .unwrap();
+        assert_eq!(dates.date, ymd(2023, 1, 31));
+        assert_eq!(dates.missing, None);
+        assert_eq!(dates.null, None);
+        assert_eq!(dates.timestamp.date(), ymd(2023, 12, 29));
+        assert_eq!(dates.months, vec![ymd(2024, 6, 30), ymd(2025, 6, 30)]);
+
+        let value = serde_json::to_value(&dates).unwrap();
+        assert_eq!(value["timestamp"], "2023-12-29T16:00:00");
+        assert_eq!(value["missing"], json!(null));
+        assert_eq!(serde_json::from_value::<Dates>(value).unwrap(), dates);
+
+        let invalid = json!({
+            "date": "", "missing": "", "null": null, "timestamp": 0, "months": []
+        });
+        assert!(serde_json::from_value::<Dates>(invalid).is_err());
+
+        // timestamp 0 is a placeholder like the string "0", years are no timestamps
+        let dates: Dates = serde_json::from_value(json!({
+            "date": "2023-01-31", "missing": "0", "null": 0, "timestamp": "1703865600",
+            "months": []
+        }))
+        .unwrap();
+        assert_eq!(dates.missing, None);
+        assert_eq!(dates.null, None);
+        let year = json!({
+            "date": "2023", "missing": "", "null": null, "timestamp": 1703865600, "months": []
+        });
+        assert!(serde_json::from_value::<Dates>(year).is_err());
+    }
+
+    #[test]
+    fn fiscal_periods() {
+        let periods: Vec<FiscalPeriod> = serde_json::from_value(json!(["2023-06", "TTM"])).unwrap();
+        assert_eq!(periods[0], FiscalPeriod::Period(ymd(2023, 6, 30)));
+        assert!(periods[1].is_ttm());
+        assert_eq!(periods[0].to_string(), "2023-06");
+        assert!(periods[0] < periods[1]);
+
+        let mid_month = FiscalPeriod::Period(ymd(2023, 6, 15));
+        let value = serde_json::to_value([periods[0], periods[1], mid_month]).unwrap();
+        assert_eq!(value, json!(["2023-06", "TTM", "2023-06-15"]));
+    }
+}
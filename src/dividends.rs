@@ -0,0 +1,397 @@
+//! Analytics of the dividend history, e.g. trailing twelve months and forward dividend,
+//! payment frequency, growth streaks, CAGR and cuts, as well as a calendar of upcoming
+//! ex-dates and pay dates for a set of symbols. Regular dividends are all dividends not
+//! marked as special (see `Dividend::is_special`); amounts are taken per share at the
+//! time of payment, use `split_adjusted` to make amounts before and after splits comparable.
+
+use chrono::{Datelike, Duration, NaiveDate};
+use futures::future;
+
+use crate::corporate_actions::{adjust_shares, CorporateAction};
+use crate::stock::{Dividend, Quote};
+use crate::strnum::FloatOrString;
+use crate::symbol::Symbol;
+use crate::{GuruFocusConnector, GuruFocusError};
+
+/// Number of most recent gaps between regular dividends used to detect the frequency
+const FREQUENCY_SAMPLE: usize = 8;
+/// Relative decrease of a regular dividend below which it is not considered a cut,
+/// e.g. due to rounding of amounts
+const CUT_TOLERANCE: f64 = 0.01;
+
+/// Payment frequency of regular dividends
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DividendFrequency {
+    Monthly,
+    Quarterly,
+    SemiAnnual,
+    Annual,
+    /// Less than two regular dividends or gaps not matching any of the above
+    Irregular,
+}
+
+impl DividendFrequency {
+    /// Returns the number of payments per year, if regular
+    pub fn payments_per_year(self) -> Option<u32> {
+        match self {
+            DividendFrequency::Monthly => Some(12),
+            DividendFrequency::Quarterly => Some(4),
+            DividendFrequency::SemiAnnual => Some(2),
+            DividendFrequency::Annual => Some(1),
+            DividendFrequency::Irregular => None,
+        }
+    }
+
+    /// Frequency matching the typical gap in days between two payments
+    fn from_gap(days: i64) -> DividendFrequency {
+        match days {
+            20..=45 => DividendFrequency::Monthly,
+            60..=120 => DividendFrequency::Quarterly,
+            150..=240 => DividendFrequency::SemiAnnual,
+            300..=420 => DividendFrequency::Annual,
+            _ => DividendFrequency::Irregular,
+        }
+    }
+}
+
+/// Decrease of a regular dividend versus the previous regular dividend
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DividendCut {
+    /// Ex-date of the reduced dividend
+    pub ex_date: NaiveDate,
+    /// Amount of the previous regular dividend
+    pub previous: f64,
+    /// Reduced amount
+    pub amount: f64,
+}
+
+/// Dividend history of a stock, ordered by ex-date with the oldest dividend first
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct DividendHistory {
+    dividends: Vec<Dividend>,
+}
+
+impl DividendHistory {
+    /// Create history from dividends in any order, e.g. as returned by `dividend_history`
+    pub fn new(mut dividends: Vec<Dividend>) -> DividendHistory {
+        dividends.sort_by_key(|dividend| dividend.ex_date);
+        DividendHistory { dividends }
+    }
+
+    /// Returns all dividends, oldest first
+    pub fn dividends(&self) -> &[Dividend] {
+        &self.dividends
+    }
+
+    /// Returns ex-date and amount of all regular dividends with numeric amount, oldest first
+    pub fn regular(&self) -> impl Iterator<Item = (NaiveDate, f64)> + '_ {
+        self.dividends
+            .iter()
+            .filter(|dividend| !dividend.is_special())
+            .filter_map(|dividend| Some((dividend.ex_date, dividend.amount.as_f64()?)))
+    }
+
+    /// Returns the history with all amounts per share adjusted for later splits contained
+    /// in `actions`, e.g. dividends before a 4:1 split are divided by 4
+    pub fn split_adjusted(&self, actions: &[CorporateAction]) -> DividendHistory {
+        let dividends = self
+            .dividends
+            .iter()
+            .map(|dividend| {
+                let mut dividend = dividend.clone();
+                if let Some(amount) = dividend.amount.as_f64() {
+                    let shares = adjust_shares(1.0, dividend.ex_date, actions);
+                    dividend.amount = FloatOrString::Value(amount / shares);
+                }
+                dividend
+            })
+            .collect();
+        DividendHistory { dividends }
+    }
+
+    /// Returns the sum of all dividends (including special dividends) with ex-date within
+    /// the twelve months up to and including `as_of`
+    pub fn ttm(&self, as_of: NaiveDate) -> f64 {
+        let start = as_of - Duration::days(365);
+        self.dividends
+            .iter()
+            .filter(|dividend| dividend.ex_date > start && dividend.ex_date <= as_of)
+            .filter_map(|dividend| dividend.amount.as_f64())
+            .sum()
+    }
+
+    /// Detect the payment frequency from the median gap between the latest regular dividends
+    pub fn frequency(&self) -> DividendFrequency {
+        let dates: Vec<NaiveDate> = self.regular().map(|(date, _)| date).collect();
+        let mut gaps: Vec<i64> = dates
+            .windows(2)
+            .rev()
+            .take(FREQUENCY_SAMPLE)
+            .map(|pair| (pair[1] - pair[0]).num_days())
+            .collect();
+        if gaps.is_empty() {
+            return DividendFrequency::Irregular;
+        }
+        gaps.sort_unstable();
+        DividendFrequency::from_gap(gaps[gaps.len() / 2])
+    }
+
+    /// Returns the latest regular dividend times the number of payments per year
+    pub fn forward_dividend(&self) -> Option<f64> {
+        let payments = self.frequency().payments_per_year()?;
+        let (_, latest) = self.regular().last()?;
+        Some(latest * payments as f64)
+    }
+
+    /// Returns the forward dividend yield based on the price of the given quote, e.g. 0.02
+    /// for 2%; the dividends are assumed to be paid in the currency of the quote
+    pub fn forward_yield(&self, quote: &Quote) -> Option<f64> {
+        let price = quote.price.as_f64().filter(|price| *price > 0.0)?;
+        Some(self.forward_dividend()? / price)
+    }
+
+    /// Returns the sum of regular dividends per calendar year of the ex-date from the first
+    /// to the last year with dividends; years without dividends in between are given as zero
+    pub fn annual_totals(&self) -> Vec<(i32, f64)> {
+        let mut totals: Vec<(i32, f64)> = Vec::new();
+        for (date, amount) in self.regular() {
+            let year = date.year();
+            if let Some((last_year, _)) = totals.last() {
+                for gap in *last_year + 1..year {
+                    totals.push((gap, 0.0));
+                }
+            }
+            match totals.last_mut() {
+                Some((last_year, total)) if *last_year == year => *total += amount,
+                _ => totals.push((year, amount)),
+            }
+        }
+        totals
+    }
+
+    /// Returns the number of consecutive complete calendar years before `as_of` with a
+    /// higher total of regular dividends than in the respective previous year
+    pub fn growth_streak(&self, as_of: NaiveDate) -> u32 {
+        let totals = self.complete_years(as_of);
+        totals
+            .windows(2)
+            .rev()
+            .take_while(|pair| pair[0].1 > 0.0 && pair[1].1 > pair[0].1)
+            .count() as u32
+    }
+
+    /// Returns the number of consecutive complete calendar years before `as_of` with
+    /// regular dividends
+    pub fn payment_streak(&self, as_of: NaiveDate) -> u32 {
+        self.complete_years(as_of)
+            .iter()
+            .rev()
+            .take_while(|(_, total)| *total > 0.0)
+            .count() as u32
+    }
+
+    /// Returns the compound annual growth rate of the annual totals of regular dividends
+    /// over the given number of years, ending with the last complete calendar year before
+    /// `as_of`, e.g. `cagr(5, as_of)`; requires positive totals at start and end
+    pub fn cagr(&self, years: u32, as_of: NaiveDate) -> Option<f64> {
+        if years == 0 {
+            return None;
+        }
+        let totals = self.complete_years(as_of);
+        let end_year = as_of.year() - 1;
+        let total = |year: i32| {
+            totals
+                .iter()
+                .find(|(y, _)| *y == year)
+                .map(|(_, total)| *total)
+        };
+        let end = total(end_year)?;
+        let start = total(end_year - years as i32)?;
+        if start > 0.0 && end > 0.0 {
+            Some((end / start).powf(1.0 / years as f64) - 1.0)
+        } else {
+            None
+        }
+    }
+
+    /// Returns all regular dividends lower than the previous regular dividend
+    pub fn cuts(&self) -> Vec<DividendCut> {
+        let regular: Vec<(NaiveDate, f64)> = self.regular().collect();
+        regular
+            .windows(2)
+            .filter(|pair| pair[1].1 < pair[0].1 * (1.0 - CUT_TOLERANCE))
+            .map(|pair| DividendCut {
+                ex_date: pair[1].0,
+                previous: pair[0].1,
+                amount: pair[1].1,
+            })
+            .collect()
+    }
+
+    /// Annual totals of all calendar years before `as_of`, including zero totals for the
+    /// years since the last dividend
+    fn complete_years(&self, as_of: NaiveDate) -> Vec<(i32, f64)> {
+        let mut totals = self.annual_totals();
+        totals.retain(|(year, _)| *year < as_of.year());
+        if let Some(&(last_year, _)) = totals.last() {
+            totals.extend((last_year + 1..as_of.year()).map(|year| (year, 0.0)));
+        }
+        totals
+    }
+}
+
+impl From<Vec<Dividend>> for DividendHistory {
+    fn from(dividends: Vec<Dividend>) -> DividendHistory {
+        DividendHistory::new(dividends)
+    }
+}
+
+/// Kind of a date of a dividend
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum DividendEventKind {
+    ExDate,
+    PayDate,
+}
+
+/// Entry of the dividend calendar
+#[derive(Debug, Clone, PartialEq)]
+pub struct DividendEvent {
+    pub date: NaiveDate,
+    pub kind: DividendEventKind,
+    pub symbol: Symbol,
+    pub dividend: Dividend,
+}
+
+/// Returns all ex-dates and pay dates on or after `from` of the dividend histories per
+/// symbol, ordered by date
+pub fn dividend_events(
+    histories: &[(Symbol, Vec<Dividend>)],
+    from: NaiveDate,
+) -> Vec<DividendEvent> {
+    let mut events: Vec<DividendEvent> = histories
+        .iter()
+        .flat_map(|(symbol, dividends)| {
+            dividends.iter().flat_map(move |dividend| {
+                let dates = vec![
+                    (Some(dividend.ex_date), DividendEventKind::ExDate),
+                    (dividend.pay_date, DividendEventKind::PayDate),
+                ];
+                dates.into_iter().filter_map(move |(date, kind)| {
+                    date.filter(|date| *date >= from).map(|date| DividendEvent {
+                        date,
+                        kind,
+                        symbol: symbol.clone(),
+                        dividend: dividend.clone(),
+                    })
+                })
+            })
+        })
+        .collect();
+    events.sort_by(|a, b| (a.date, &a.symbol, a.kind).cmp(&(b.date, &b.symbol, b.kind)));
+    events
+}
+
+impl GuruFocusConnector {
+    /// Returns the calendar of ex-dates and pay dates on or after `from` of the given
+    /// symbols, see `dividend_events`. The dividend histories are requested concurrently.
+    pub async fn dividend_calendar(
+        &self,
+        stocks: &[impl Into<Symbol> + Clone],
+        from: NaiveDate,
+    ) -> Result<Vec<DividendEvent>, GuruFocusError> {
+        let symbols: Vec<Symbol> = stocks.iter().cloned().map(Into::into).collect();
+        let histories = future::try_join_all(symbols.into_iter().map(|symbol| async move {
+            let dividends = self.dividend_history(&symbol).await?;
+            Ok::<_, GuruFocusError>((symbol, dividends))
+        }))
+        .await?;
+        Ok(dividend_events(&histories, from))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::replay::fixture_connector;
+    use crate::test_util::{assert_close, ymd};
+
+    #[tokio::test]
+    async fn dividend_analytics_from_fixture() {
+        let gf_connect = fixture_connector();
+        let history = DividendHistory::new(gf_connect.dividend_history("NAS:MSFT").await.unwrap());
+        let quotes = gf_connect.quotes(&["NAS:MSFT", "NAS:AAPL"]).await.unwrap();
+        let as_of = ymd(2024, 1, 1);
+        assert_eq!(history.dividends()[0].ex_date, ymd(2004, 8, 23));
+        assert_close(history.ttm(as_of), 0.68 * 3.0 + 0.75, 1e-9);
+        assert_close(history.ttm(ymd(2004, 12, 31)), 3.08, 1e-9);
+        assert_eq!(history.frequency(), DividendFrequency::Quarterly);
+        assert_close(history.forward_dividend().unwrap(), 3.0, 1e-9);
+        assert_close(
+            history.forward_yield(&quotes[0]).unwrap(),
+            3.0 / 376.04,
+            1e-9,
+        );
+
+        let totals = history.annual_totals();
+        assert_eq!(totals.len(), 20);
+        assert_eq!(totals[0], (2004, 0.08));
+        assert_eq!(totals[1], (2005, 0.0));
+        assert_eq!(history.payment_streak(as_of), 10);
+        assert_eq!(history.growth_streak(as_of), 9);
+        assert_eq!(history.growth_streak(ymd(2023, 6, 30)), 8);
+        assert_eq!(history.growth_streak(ymd(2026, 1, 1)), 0);
+        assert_eq!(history.payment_streak(ymd(2026, 1, 1)), 0);
+        assert_close(history.cagr(1, as_of).unwrap(), 2.79 / 2.54 - 1.0, 1e-9);
+        assert_close(
+            history.cagr(5, as_of).unwrap(),
+            (2.79f64 / 1.72).powf(0.2) - 1.0,
+            1e-9,
+        );
+        assert_eq!(history.cagr(10, as_of), None);
+        assert!(history.cuts().is_empty());
+
+        let events = gf_connect
+            .dividend_calendar(&["NAS:MSFT", "NAS:AAPL"], ymd(2020, 11, 7))
+            .await
+            .unwrap();
+        assert_eq!(events.len(), 27);
+        assert_eq!(events[0].date, ymd(2020, 11, 15));
+        assert_eq!(events[0].kind, DividendEventKind::ExDate);
+        assert_eq!(events[0].symbol, "NAS:MSFT");
+        assert_eq!(events[1].date, ymd(2020, 12, 4));
+        assert_eq!(events[1].kind, DividendEventKind::PayDate);
+        assert_eq!(events[1].symbol, "NAS:AAPL");
+        assert!(events.windows(2).all(|pair| pair[0].date <= pair[1].date));
+    }
+
+    #[tokio::test]
+    async fn cuts_and_splits() {
+        let gf_connect = fixture_connector();
+        let adjusted = gf_connect.price_hist("NAS:AAPL").await.unwrap();
+        let unadjusted = gf_connect.unadj_price_hist("NAS:AAPL").await.unwrap();
+        let dividends = gf_connect.dividend_history("NAS:AAPL").await.unwrap();
+        let actions = crate::detect_corporate_actions(&adjusted, &unadjusted, &dividends);
+        let history = DividendHistory::from(dividends);
+        assert_eq!(
+            history.cuts(),
+            vec![DividendCut {
+                ex_date: ymd(2020, 11, 6),
+                previous: 0.82,
+                amount: 0.205
+            }]
+        );
+        let adjusted = history.split_adjusted(&actions);
+        assert!(adjusted.cuts().is_empty());
+        assert_close(
+            adjusted.ttm(ymd(2020, 12, 31)),
+            (0.77 + 0.82 * 2.0) / 4.0 + 0.205,
+            1e-9,
+        );
+        assert_eq!(adjusted.frequency(), DividendFrequency::Quarterly);
+        assert_eq!(
+            DividendHistory::default().frequency(),
+            DividendFrequency::Irregular
+        );
+        assert_eq!(DividendHistory::default().forward_dividend(), None);
+    }
+}
@@ -0,0 +1,85 @@
+use std::fmt;
+
+/// The GuruFocus API endpoints, used e.g. to count the requests per endpoint
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Endpoint {
+    Financials,
+    KeyRatios,
+    Quote,
+    Price,
+    UnadjustedPrice,
+    Summary,
+    GuruTrades,
+    InsiderTrades,
+    GuruList,
+    GuruPicks,
+    GuruPortfolios,
+    ExchangeList,
+    ExchangeStocks,
+    InsiderUpdates,
+    Dividend,
+    AnalystEstimate,
+    PersonalPortfolio,
+    FundamentalsUpdated,
+    Politicians,
+    PoliticianTransactions,
+}
+
+impl Endpoint {
+    /// List of all endpoints
+    pub const ALL: [Endpoint; 20] = [
+        Endpoint::Financials,
+        Endpoint::KeyRatios,
+        Endpoint::Quote,
+        Endpoint::Price,
+        Endpoint::UnadjustedPrice,
+        Endpoint::Summary,
+        Endpoint::GuruTrades,
+        Endpoint::InsiderTrades,
+        Endpoint::GuruList,
+        Endpoint::GuruPicks,
+        Endpoint::GuruPortfolios,
+        Endpoint::ExchangeList,
+        Endpoint::ExchangeStocks,
+        Endpoint::InsiderUpdates,
+        Endpoint::Dividend,
+        Endpoint::AnalystEstimate,
+        Endpoint::PersonalPortfolio,
+        Endpoint::FundamentalsUpdated,
+        Endpoint::Politicians,
+        Endpoint::PoliticianTransactions,
+    ];
+
+    /// Stable identifier of the endpoint used in request statistics and cache keys, derived
+    /// from, but not always identical to the request path
+    pub fn name(&self) -> &'static str {
+        match self {
+            Endpoint::Financials => "financials",
+            Endpoint::KeyRatios => "keyratios",
+            Endpoint::Quote => "quote",
+            Endpoint::Price => "price",
+            Endpoint::UnadjustedPrice => "unadjusted_price",
+            Endpoint::Summary => "summary",
+            Endpoint::GuruTrades => "gurus",
+            Endpoint::InsiderTrades => "insider",
+            Endpoint::GuruList => "gurulist",
+            Endpoint::GuruPicks => "picks",
+            Endpoint::GuruPortfolios => "aggregated",
+            Endpoint::ExchangeList => "exchange_list",
+            Endpoint::ExchangeStocks => "exchange_stocks",
+            Endpoint::InsiderUpdates => "insider_updates",
+            Endpoint::Dividend => "dividend",
+            Endpoint::AnalystEstimate => "analyst_estimate",
+            Endpoint::PersonalPortfolio => "my_portfolios",
+            Endpoint::FundamentalsUpdated => "funda_updated",
+            Endpoint::Politicians => "politicians",
+            Endpoint::PoliticianTransactions => "politician_transactions",
+        }
+    }
+}
+
+impl fmt::Display for Endpoint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
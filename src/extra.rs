@@ -0,0 +1,239 @@
+//! GuruFocus frequently adds new fields to the JSON responses. To avoid breaking the
+//! deserialization each time this happens, all data structs capture fields they do not
+//! know into a member `extra` of type `Extra`. Whether such fields are treated as an
+//! error (`FieldMode::Strict`) or are just reported (`FieldMode::Lenient`) is decided
+//! at runtime, when deserializing via `parse_json_with` or `GuruFocusConnector`.
+
+use serde::de::{Deserialize, DeserializeOwned, Deserializer};
+use serde::{Serialize, Serializer};
+use serde_json::Value;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt;
+use std::marker::PhantomData;
+use std::ops::{Deref, DerefMut};
+use std::sync::Arc;
+
+use crate::GuruFocusError;
+
+/// Treatment of fields in the JSON data which have no counterpart in the data structs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FieldMode {
+    /// Unknown fields result in a `GuruFocusError::UnknownFields` error
+    #[default]
+    Strict,
+    /// Unknown fields are kept in the `extra` member of the data struct and reported
+    Lenient,
+}
+
+/// Description of a field which is unknown to the data struct it has been found in
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownField {
+    /// Name of the data struct
+    pub type_name: &'static str,
+    /// Name of the unknown field
+    pub field: String,
+}
+
+impl fmt::Display for UnknownField {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}.{}", self.type_name, self.field)
+    }
+}
+
+/// Callback invoked for each unknown field found in lenient mode
+#[derive(Clone)]
+pub(crate) struct UnknownFieldHandler(pub(crate) Arc<dyn Fn(&UnknownField) + Send + Sync>);
+
+impl fmt::Debug for UnknownFieldHandler {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("UnknownFieldHandler")
+    }
+}
+
+/// Fields of the JSON object not known to data struct `T`
+pub struct Extra<T> {
+    fields: HashMap<String, Value>,
+    owner: PhantomData<fn() -> T>,
+}
+
+impl<T> Extra<T> {
+    /// Consume container and return the map of unknown fields
+    pub fn into_inner(self) -> HashMap<String, Value> {
+        self.fields
+    }
+}
+
+impl<T> Deref for Extra<T> {
+    type Target = HashMap<String, Value>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.fields
+    }
+}
+
+impl<T> DerefMut for Extra<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.fields
+    }
+}
+
+impl<T> Default for Extra<T> {
+    fn default() -> Self {
+        Extra {
+            fields: HashMap::new(),
+            owner: PhantomData,
+        }
+    }
+}
+
+impl<T> Clone for Extra<T> {
+    fn clone(&self) -> Self {
+        Extra {
+            fields: self.fields.clone(),
+            owner: PhantomData,
+        }
+    }
+}
+
+impl<T> PartialEq for Extra<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.fields == other.fields
+    }
+}
+
+/// Unknown fields are serialized as they have been found, i.e. as members of the data
+/// struct if flattened into it
+impl<T> Serialize for Extra<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.fields.serialize(serializer)
+    }
+}
+
+impl<T> fmt::Debug for Extra<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.fields.fmt(f)
+    }
+}
+
+impl<'de, T> Deserialize<'de> for Extra<T> {
+    fn deserialize<D>(deserializer: D) -> Result<Extra<T>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let fields = HashMap::<String, Value>::deserialize(deserializer)?;
+        if !fields.is_empty() {
+            UNKNOWN_FIELDS.with(|unknown| {
+                if let Some(unknown) = unknown.borrow_mut().as_mut() {
+                    let type_name = short_type_name::<T>();
+                    unknown.extend(fields.keys().map(|field| UnknownField {
+                        type_name,
+                        field: field.clone(),
+                    }));
+                }
+            });
+        }
+        Ok(Extra {
+            fields,
+            owner: PhantomData,
+        })
+    }
+}
+
+thread_local! {
+    /// Unknown fields found while deserializing on this thread, if recording is active
+    static UNKNOWN_FIELDS: RefCell<Option<Vec<UnknownField>>> = const { RefCell::new(None) };
+}
+
+/// Type name without module path
+fn short_type_name<T>() -> &'static str {
+    let name = std::any::type_name::<T>();
+    name.rsplit("::").next().unwrap_or(name)
+}
+
+/// Deserialize a JSON value into a data struct and return all unknown fields found
+/// in the struct or any of its members. In strict mode, unknown fields result in an error.
+pub fn parse_json_with<T: DeserializeOwned>(
+    value: Value,
+    mode: FieldMode,
+) -> Result<(T, Vec<UnknownField>), GuruFocusError> {
+    let previous = UNKNOWN_FIELDS.with(|unknown| unknown.replace(Some(Vec::new())));
+    let result = serde_json::from_value::<T>(value);
+    let unknown = UNKNOWN_FIELDS
+        .with(|unknown| unknown.replace(previous))
+        .unwrap_or_default();
+    let data = result.map_err(|source| GuruFocusError::SchemaMismatch {
+        type_name: std::any::type_name::<T>(),
+        source,
+    })?;
+    if mode == FieldMode::Strict && !unknown.is_empty() {
+        return Err(GuruFocusError::UnknownFields {
+            type_name: std::any::type_name::<T>(),
+            fields: unknown,
+        });
+    }
+    Ok((data, unknown))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gurus::GuruTrades;
+    use crate::Stock;
+
+    const STOCK: &str = r#"{
+        "company": "Equinor ASA",
+        "currency": "NOK",
+        "exchange": "OSL",
+        "industry": "Oil & Gas",
+        "sector": "Energy",
+        "subindustry": "Integrated",
+        "symbol": "EQNR",
+        "isin": "NO0010096985"
+    }"#;
+
+    #[test]
+    fn strict_mode_rejects_unknown_fields() {
+        let value: Value = serde_json::from_str(STOCK).unwrap();
+        let err = parse_json_with::<Stock>(value, FieldMode::Strict).unwrap_err();
+        match err {
+            GuruFocusError::UnknownFields { fields, .. } => {
+                assert_eq!(fields.len(), 1);
+                assert_eq!(fields[0].to_string(), "Stock.isin");
+            }
+            _ => panic!("unexpected error {:?}", err),
+        }
+    }
+
+    #[test]
+    fn lenient_mode_captures_unknown_fields() {
+        let value: Value = serde_json::from_str(STOCK).unwrap();
+        let (stock, unknown) = parse_json_with::<Stock>(value, FieldMode::Lenient).unwrap();
+        assert_eq!(stock.symbol, "EQNR");
+        assert_eq!(stock.extra["isin"], "NO0010096985");
+        assert_eq!(unknown.len(), 1);
+        // unknown fields are kept when serializing the struct again
+        let value = serde_json::to_value(&stock).unwrap();
+        assert_eq!(value["isin"], "NO0010096985");
+        let (copy, _) = parse_json_with::<Stock>(value, FieldMode::Lenient).unwrap();
+        assert_eq!(copy, stock);
+    }
+
+    #[test]
+    fn unknown_fields_in_nested_structs() {
+        let value: Value = serde_json::from_str(
+            r#"{"WMT": {"holdings": [], "picks": [], "new": 1}, "KO": {"holdings": [], "picks": []}}"#,
+        )
+        .unwrap();
+        let (trades, unknown) =
+            parse_json_with::<HashMap<String, GuruTrades>>(value, FieldMode::Lenient).unwrap();
+        assert_eq!(trades.len(), 2);
+        assert_eq!(
+            unknown,
+            vec![UnknownField {
+                type_name: "GuruTrades",
+                field: "new".to_string()
+            }]
+        );
+    }
+}
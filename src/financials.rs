@@ -9,30 +9,35 @@
 /// A synopsis of all financial data structs could be found here:
 /// https://github.com/xemwebe/gurufocus_api/blob/master/FinancialDataSynopsis.ods
 ///
-use serde::Deserialize;
+use crate::dates::FiscalPeriod;
+use crate::extra::Extra;
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
 pub use crate::strnum::FloatOrString;
 
 /// Structure holding the history of financial data for a single stock.
-#[derive(Deserialize, Debug)]
-#[serde(deny_unknown_fields)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct FinancialData {
     pub financials: DataPeriods,
+    /// Fields unknown to this crate, see `FieldMode`
+    #[serde(flatten)]
+    pub extra: Extra<FinancialData>,
 }
 
 /// Structure holding the history of financial for different periods.
-#[derive(Deserialize, Debug)]
-#[serde(deny_unknown_fields)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct DataPeriods {
     pub financial_template_parameters: FinancialTemplateParameters,
     pub annuals: PeriodData,
     pub quarterly: PeriodData,
+    /// Fields unknown to this crate, see `FieldMode`
+    #[serde(flatten)]
+    pub extra: Extra<DataPeriods>,
 }
 
 /// Structure parameters for specific financial data template
-#[derive(Deserialize, Debug)]
-#[serde(deny_unknown_fields)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct FinancialTemplateParameters {
     pub ind_template: String,
     #[serde(rename = "REITs")]
@@ -40,14 +45,16 @@ pub struct FinancialTemplateParameters {
     #[serde(rename = "IsDirect")]
     pub is_direct: String,
     pub financial_report_frequency: String,
+    /// Fields unknown to this crate, see `FieldMode`
+    #[serde(flatten)]
+    pub extra: Extra<FinancialTemplateParameters>,
 }
 
 /// Structure holding the history of financial for annual or quarterly period.
-#[derive(Deserialize, Debug)]
-#[serde(deny_unknown_fields)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct PeriodData {
     #[serde(rename = "Fiscal Year")]
-    pub fiscal_year: Vec<String>,
+    pub fiscal_year: Vec<FiscalPeriod>,
     #[serde(rename = "Preliminary")]
     pub preliminary: Vec<FloatOrString>,
     pub per_share_data_array: Value,
@@ -57,14 +64,35 @@ pub struct PeriodData {
     pub cashflow_statement: Value,
     pub valuation_ratios: Value,
     pub valuation_and_quality: Value,
+    /// Fields unknown to this crate, see `FieldMode`
+    #[serde(flatten)]
+    pub extra: Extra<PeriodData>,
 }
 
 #[cfg(test)]
 mod tests {
     use super::super::*;
-    use super::*;
+    use crate::replay::{assert_round_trip, fixture_connector};
     use std::env;
 
+    #[tokio::test]
+    async fn financials_from_fixture() {
+        let gf_connect = fixture_connector();
+        let financials = gf_connect.financials("NAS:MSFT").await.unwrap().financials;
+        assert_eq!(financials.financial_template_parameters.ind_template, "N");
+        assert_eq!(financials.annuals.fiscal_year.len(), 6);
+        assert_eq!(financials.annuals.fiscal_year[5], FiscalPeriod::Ttm);
+        assert_eq!(
+            financials.annuals.fiscal_year[4].end_date(),
+            chrono::NaiveDate::from_ymd_opt(2023, 6, 30)
+        );
+        assert_eq!(
+            financials.quarterly.income_statement["Revenue"][4],
+            "62020.000"
+        );
+        assert_round_trip(&financials);
+    }
+
     #[tokio::test]
     async fn test_financials_non_financial() {
         if let Ok(token) = env::var("GURUFOCUS_TOKEN") {
@@ -78,6 +106,17 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_financials_non_financial_typed() {
+        if let Ok(token) = env::var("GURUFOCUS_TOKEN") {
+            if !token.is_empty() {
+                let gf_connect = GuruFocusConnector::new(token);
+                let financials = gf_connect.financials("AMZN").await;
+                assert!(financials.is_ok());
+            }
+        }
+    }
+
     #[tokio::test]
     async fn test_financials_bank() {
         if let Ok(token) = env::var("GURUFOCUS_TOKEN") {
@@ -91,6 +130,17 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_financials_bank_typed() {
+        if let Ok(token) = env::var("GURUFOCUS_TOKEN") {
+            if !token.is_empty() {
+                let gf_connect = GuruFocusConnector::new(token);
+                let financials = gf_connect.financials("NYSE:JPM").await;
+                assert!(financials.is_ok());
+            }
+        }
+    }
+
     #[tokio::test]
     async fn test_financials_insurance() {
         if let Ok(token) = env::var("GURUFOCUS_TOKEN") {
@@ -104,6 +154,17 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_financials_insurance_typed() {
+        if let Ok(token) = env::var("GURUFOCUS_TOKEN") {
+            if !token.is_empty() {
+                let gf_connect = GuruFocusConnector::new(token);
+                let financials = gf_connect.financials("AIG").await;
+                assert!(financials.is_ok());
+            }
+        }
+    }
+
     #[tokio::test]
     async fn test_financials_reit() {
         if let Ok(token) = env::var("GURUFOCUS_TOKEN") {
@@ -116,4 +177,15 @@ mod tests {
             }
         }
     }
+
+    #[tokio::test]
+    async fn test_financials_reit_typed() {
+        if let Ok(token) = env::var("GURUFOCUS_TOKEN") {
+            if !token.is_empty() {
+                let gf_connect = GuruFocusConnector::new(token);
+                let financials = gf_connect.financials("GOOD").await;
+                assert!(financials.is_ok());
+            }
+        }
+    }
 }
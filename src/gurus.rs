@@ -1,11 +1,13 @@
-use serde::Deserialize;
+use crate::extra::Extra;
+use crate::symbol::Symbol;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 pub use crate::strnum::FloatOrString;
 
 /// Structure holding basic data for a single Guru.
-#[derive(Deserialize, Debug)]
-#[serde(deny_unknown_fields)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct Guru {
     /// Unique identifier for a Guru.
     pub id: String,
@@ -22,76 +24,93 @@ pub struct Guru {
     /// Turnover rate in %.
     pub turnover: FloatOrString,
     /// Date of latest update of this data.
-    pub latest_update: String,
+    #[serde(default, with = "crate::dates::option_date")]
+    pub latest_update: Option<NaiveDate>,
+    /// Fields unknown to this crate, see `FieldMode`
+    #[serde(flatten)]
+    pub extra: Extra<Guru>,
 }
 
 /// Container for all basic Guru data.
-#[derive(Deserialize, Debug)]
-#[serde(deny_unknown_fields)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct Gurus {
     /// Map holding the list of Gurus per country.
     pub all: HashMap<String, Vec<Guru>>,
     /// For each personal list of Gurus (e.g. default and custom list),
     /// a vector of Guru IDs is stored.
     pub my: HashMap<String, Vec<String>>,
+    /// Fields unknown to this crate, see `FieldMode`
+    #[serde(flatten)]
+    pub extra: Extra<Gurus>,
 }
 
 /// Container for the guru holdings
-#[derive(Deserialize, Debug)]
-#[serde(deny_unknown_fields)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct GuruTrades {
     /// Array of gurus position in the stock
     pub holdings: Vec<GuruHoldings>,
     /// Array of recent guru picks in the stock
     pub picks: Vec<GuruPicks2>,
+    /// Fields unknown to this crate, see `FieldMode`
+    #[serde(flatten)]
+    pub extra: Extra<GuruTrades>,
 }
 
 /// Container for the guru holdings
-#[derive(Deserialize, Debug)]
-#[serde(deny_unknown_fields)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct GuruHoldings {
     pub change: FloatOrString,
     pub current_shares: FloatOrString,
-    pub date: String,
+    #[serde(with = "crate::dates::date")]
+    pub date: NaiveDate,
     pub guru: String,
     pub guru_id: String,
     pub perc_assets: FloatOrString,
     pub perc_shares: FloatOrString,
+    /// Fields unknown to this crate, see `FieldMode`
+    #[serde(flatten)]
+    pub extra: Extra<GuruHoldings>,
 }
 
 /// Container for the guru holdings
-#[derive(Deserialize, Debug)]
-#[serde(deny_unknown_fields)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct GuruPicks2 {
     #[serde(rename = "Avg")]
     pub avg: FloatOrString,
     pub action: String,
     pub comment: String,
     pub current_shares: FloatOrString,
-    pub date: String,
+    #[serde(with = "crate::dates::date")]
+    pub date: NaiveDate,
     pub guru: String,
     pub guru_id: String,
     pub impact: FloatOrString,
     pub price_max: FloatOrString,
     pub price_min: FloatOrString,
+    /// Fields unknown to this crate, see `FieldMode`
+    #[serde(flatten)]
+    pub extra: Extra<GuruPicks2>,
 }
 
-// #[derive(Deserialize, Debug)]
-// #[serde(deny_unknown_fields)]
-// pub struct GuruPicks {
-//     /// Portfolio of guru picks
-//     pub picks: Vec<GuruPick>,
-// }
+/// Container for the stock picks of a single guru
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct GuruPicks {
+    /// Portfolio of guru picks
+    pub picks: Vec<GuruPick>,
+    /// Fields unknown to this crate, see `FieldMode`
+    #[serde(flatten)]
+    pub extra: Extra<GuruPicks>,
+}
 
-#[derive(Deserialize, Debug)]
-#[serde(deny_unknown_fields)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct GuruPick {
     #[serde(rename = "GuruName")]
     pub guru_name: String,
     #[serde(rename = "RecmAction")]
     pub recm_action: String,
     #[serde(rename = "RecmDate")]
-    pub recm_date: String,
+    #[serde(with = "crate::dates::date")]
+    pub recm_date: NaiveDate,
     #[serde(rename = "RecmPrice")]
     pub recm_price: FloatOrString,
     pub change: FloatOrString,
@@ -104,39 +123,47 @@ pub struct GuruPick {
     pub price_min: FloatOrString,
     pub sector: String,
     pub share_current: FloatOrString,
-    pub symbol: String,
+    pub symbol: Symbol,
     pub symbol_ori: String,
     pub trans_share: FloatOrString,
     #[serde(rename = "type")]
     pub transaction_type: String,
     pub exchange: String,
     pub industry: String,
+    /// Fields unknown to this crate, see `FieldMode`
+    #[serde(flatten)]
+    pub extra: Extra<GuruPick>,
 }
 
-#[derive(Deserialize, Debug)]
-#[serde(deny_unknown_fields)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct GuruPortfolio {
     pub summary: GuruPortSummary,
     pub port: Vec<GuruPosition>,
+    /// Fields unknown to this crate, see `FieldMode`
+    #[serde(flatten)]
+    pub extra: Extra<GuruPortfolio>,
 }
 
-#[derive(Deserialize, Debug)]
-#[serde(deny_unknown_fields)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct GuruPortSummary {
     pub country: String,
-    pub date: String,
+    #[serde(with = "crate::dates::date")]
+    pub date: NaiveDate,
     pub equity: FloatOrString,
     pub firm: String,
     pub num_new: FloatOrString,
     pub number_of_stocks: FloatOrString,
     pub turnover: FloatOrString,
+    /// Fields unknown to this crate, see `FieldMode`
+    #[serde(flatten)]
+    pub extra: Extra<GuruPortSummary>,
 }
 
-#[derive(Deserialize, Debug)]
-#[serde(deny_unknown_fields)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct GuruPosition {
     #[serde(rename = "13f_date")]
-    pub date_13f: String,
+    #[serde(with = "crate::dates::date")]
+    pub date_13f: NaiveDate,
     #[serde(rename = "52h")]
     pub num_52h: FloatOrString,
     #[serde(rename = "52l")]
@@ -155,16 +182,18 @@ pub struct GuruPosition {
     pub price: FloatOrString,
     pub sector: String,
     pub share: FloatOrString,
-    pub symbol: String,
+    pub symbol: Symbol,
     pub symbol_ori: String,
     pub value: FloatOrString,
     #[serde(rename = "yield")]
     pub transaction_yield: FloatOrString,
+    /// Fields unknown to this crate, see `FieldMode`
+    #[serde(flatten)]
+    pub extra: Extra<GuruPosition>,
 }
 
 /// Politicians
-#[derive(Deserialize, Debug)]
-#[serde(deny_unknown_fields)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct Politician {
     /// Unique identifier of politician
     pub id: u32,
@@ -178,13 +207,15 @@ pub struct Politician {
     pub district: Option<String>,
     /// Politicians state
     pub state: String,
+    /// Fields unknown to this crate, see `FieldMode`
+    #[serde(flatten)]
+    pub extra: Extra<Politician>,
 }
 
 /// Politicians transactions
-#[derive(Deserialize, Debug)]
-#[serde(deny_unknown_fields)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct PoliticianTransaction {
-    pub symbol: String,
+    pub symbol: Symbol,
     pub company: String,
     pub exchange: String,
     pub industry: usize,
@@ -194,19 +225,25 @@ pub struct PoliticianTransaction {
     pub strike_price: Option<FloatOrString>,
     pub trans_type: String,
     pub amount: String,
-    pub disclosure_date: String,
-    pub transaction_date: String,
-    pub expiration_date: Option<String>,
+    #[serde(with = "crate::dates::date")]
+    pub disclosure_date: NaiveDate,
+    #[serde(with = "crate::dates::date")]
+    pub transaction_date: NaiveDate,
+    #[serde(default, with = "crate::dates::option_date")]
+    pub expiration_date: Option<NaiveDate>,
     pub id: u32,
     pub full_name: String,
     pub official_full: Option<String>,
     pub position: String,
     pub state: String,
     pub party: String,
+    /// Fields unknown to this crate, see `FieldMode`
+    #[serde(flatten)]
+    pub extra: Extra<PoliticianTransaction>,
 }
 
 /// Asset type traded by politicians
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
 pub enum AssetType {
     #[serde(rename = "Common Stock")]
     CommonStock,
@@ -222,7 +259,7 @@ pub enum AssetType {
 }
 
 /// List of politician transactions
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct PoliticianTransactionList {
     pub count: usize,
     #[serde(rename = "currentPage")]
@@ -231,16 +268,82 @@ pub struct PoliticianTransactionList {
     pub last_page: u32,
     pub total: u32,
     pub data: Vec<PoliticianTransaction>,
+    /// Fields unknown to this crate, see `FieldMode`
+    #[serde(flatten)]
+    pub extra: Extra<PoliticianTransactionList>,
 }
 
 #[cfg(test)]
 mod tests {
     use super::super::*;
     use super::*;
+    use crate::replay::{assert_round_trip, fixture_connector};
     use crate::serde_json::Error;
     use chrono::{Datelike, NaiveDate, Utc};
     use std::env;
 
+    #[tokio::test]
+    async fn guru_trades_from_fixture() {
+        let gf_connect = fixture_connector();
+        let trades = gf_connect.guru_trades("WMT").await.unwrap();
+        let trades = &trades["WMT"];
+        assert_eq!(trades.holdings.len(), 2);
+        assert_eq!(trades.picks[0].action, "Reduce");
+        assert_round_trip(trades);
+    }
+
+    #[tokio::test]
+    async fn gurus_from_fixture() {
+        let gf_connect = fixture_connector();
+        let gurus = gf_connect.gurus().await.unwrap();
+        assert_eq!(gurus.all["US"][0].name, "Warren Buffett");
+        assert!(gurus.all["US"][1].url.is_none());
+        assert_eq!(gurus.my["default"], vec!["7", "28"]);
+        assert_round_trip(&gurus);
+    }
+
+    #[tokio::test]
+    async fn guru_picks_from_fixture() {
+        let gf_connect = fixture_connector();
+        let start = NaiveDate::from_ymd_opt(2023, 7, 1).unwrap();
+        let picks = gf_connect.guru_picks(&["7", "16"], start, 1).await.unwrap();
+        assert_eq!(picks["7"].picks[0].symbol, "AAPL");
+        assert_eq!(picks["16"].picks[0].transaction_type, "Add");
+        assert_round_trip(&picks);
+        let picks = gf_connect.guru_picks(&["7", "16"], start, 2).await.unwrap();
+        assert!(picks.values().all(|p| p.picks.is_empty()));
+    }
+
+    #[tokio::test]
+    async fn guru_portfolios_from_fixture() {
+        let gf_connect = fixture_connector();
+        let portfolios = gf_connect.guru_portfolios(&["7"]).await.unwrap();
+        let portfolio = &portfolios["7"];
+        assert_eq!(portfolio.summary.firm, "Berkshire Hathaway");
+        assert_eq!(
+            portfolio.port[0].date_13f,
+            NaiveDate::from_ymd_opt(2023, 9, 30).unwrap()
+        );
+        assert_round_trip(&portfolios);
+    }
+
+    #[tokio::test]
+    async fn politicians_from_fixture() {
+        let gf_connect = fixture_connector();
+        let politicians = gf_connect.politicians().await.unwrap();
+        assert_eq!(politicians.len(), 2);
+        assert_eq!(politicians[1].district.as_deref(), Some("CA12"));
+        let transactions = gf_connect.politician_transactions(1, None).await.unwrap();
+        assert_eq!(transactions.last_page, 1);
+        assert!(matches!(
+            transactions.data[1].class,
+            gurus::AssetType::Option
+        ));
+        assert_eq!(transactions.data[1].option_type.as_deref(), Some("Call"));
+        assert_round_trip(&politicians);
+        assert_round_trip(&transactions);
+    }
+
     #[tokio::test]
     async fn test_guru_trades() {
         if let Ok(token) = env::var("GURUFOCUS_TOKEN") {
@@ -255,6 +358,18 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_guru_trades_typed() {
+        if let Ok(token) = env::var("GURUFOCUS_TOKEN") {
+            if !token.is_empty() {
+                let gf_connect = GuruFocusConnector::new(token);
+                let stock = "WMT";
+                let trades = gf_connect.guru_trades(stock).await;
+                assert!(trades.is_ok());
+            }
+        }
+    }
+
     fn get_days_from_month(year: i32, month: u32) -> u32 {
         NaiveDate::from_ymd_opt(
             match month {
@@ -308,6 +423,22 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_guru_picks_typed() {
+        if let Ok(token) = env::var("GURUFOCUS_TOKEN") {
+            if !token.is_empty() {
+                let gf_connect = GuruFocusConnector::new(token);
+                // Buffett, Soros and Klarman
+                let gurus = ["7", "16", "28"];
+                let now = Utc::now().naive_local().date();
+                let three_months_ago = month_before(now, 3);
+                let page = 1;
+                let trades = gf_connect.guru_picks(&gurus, three_months_ago, page).await;
+                assert!(trades.is_ok());
+            }
+        }
+    }
+
     #[tokio::test]
     async fn test_gurulist() {
         if let Ok(token) = env::var("GURUFOCUS_TOKEN") {
@@ -319,6 +450,17 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_gurulist_typed() {
+        if let Ok(token) = env::var("GURUFOCUS_TOKEN") {
+            if !token.is_empty() {
+                let gf_connect = GuruFocusConnector::new(token);
+                let guru_data = gf_connect.gurus().await;
+                assert!(guru_data.is_ok());
+            }
+        }
+    }
+
     #[tokio::test]
     async fn test_guru_portfolios() {
         if let Ok(token) = env::var("GURUFOCUS_TOKEN") {
@@ -335,6 +477,19 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_guru_portfolios_typed() {
+        if let Ok(token) = env::var("GURUFOCUS_TOKEN") {
+            if !token.is_empty() {
+                let gf_connect = GuruFocusConnector::new(token);
+                // Bill Ackman and David Einhorn
+                let gurus = ["47", "39"];
+                let portfolios = gf_connect.guru_portfolios(&gurus).await;
+                assert!(portfolios.is_ok());
+            }
+        }
+    }
+
     #[tokio::test]
     async fn test_politicianlist() {
         if let Ok(token) = env::var("GURUFOCUS_TOKEN") {
@@ -346,6 +501,17 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_politicianlist_typed() {
+        if let Ok(token) = env::var("GURUFOCUS_TOKEN") {
+            if !token.is_empty() {
+                let gf_connect = GuruFocusConnector::new(token);
+                let politicians = gf_connect.politicians().await;
+                assert!(politicians.is_ok());
+            }
+        }
+    }
+
     #[tokio::test]
     async fn test_politiciantransactions() {
         if let Ok(token) = env::var("GURUFOCUS_TOKEN") {
@@ -359,4 +525,15 @@ mod tests {
             }
         }
     }
+
+    #[tokio::test]
+    async fn test_politiciantransactions_typed() {
+        if let Ok(token) = env::var("GURUFOCUS_TOKEN") {
+            if !token.is_empty() {
+                let gf_connect = GuruFocusConnector::new(token);
+                let politician_transactions = gf_connect.politician_transactions(1, None).await;
+                assert!(politician_transactions.is_ok());
+            }
+        }
+    }
 }
@@ -1,11 +1,11 @@
 use serde::de::{self, Visitor};
-use serde::{Deserialize, Deserializer};
+use serde::{Deserialize, Deserializer, Serialize};
 use std::default;
 use std::fmt;
 use std::marker::PhantomData;
 use std::str::FromStr;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct HexNum(String);
 
 impl<'de> Deserialize<'de> for HexNum {
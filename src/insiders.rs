@@ -1,14 +1,17 @@
-use serde::Deserialize;
+use crate::extra::Extra;
+use crate::symbol::Symbol;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
 
 pub use crate::strnum::FloatOrString;
 
 /// Container for the insider trades
-#[derive(Deserialize, Debug)]
-#[serde(deny_unknown_fields)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct InsiderTrade {
     pub change: FloatOrString,
     pub cost: FloatOrString,
-    pub date: String,
+    #[serde(with = "crate::dates::date")]
+    pub date: NaiveDate,
     pub final_share: FloatOrString,
     pub insider: String,
     pub position: String,
@@ -16,32 +19,48 @@ pub struct InsiderTrade {
     pub trans_share: FloatOrString,
     #[serde(rename = "type")]
     pub trade_type: String,
+    /// Fields unknown to this crate, see `FieldMode`
+    #[serde(flatten)]
+    pub extra: Extra<InsiderTrade>,
 }
 
 /// Container for latest updates on insider trades
-#[derive(Deserialize, Debug)]
-#[serde(deny_unknown_fields)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct InsiderUpdate {
     pub final_share: FloatOrString,
     pub insider: String,
-    pub date: String,
+    #[serde(with = "crate::dates::date")]
+    pub date: NaiveDate,
     pub position: String,
     pub price: FloatOrString,
-    pub symbol: String,
+    pub symbol: Symbol,
     pub cost: FloatOrString,
     pub exchange: String,
     pub trans_share: FloatOrString,
     #[serde(rename = "type")]
     pub trade_type: String,
+    /// Fields unknown to this crate, see `FieldMode`
+    #[serde(flatten)]
+    pub extra: Extra<InsiderUpdate>,
 }
 
 #[cfg(test)]
 mod test {
     use super::super::*;
-    use super::*;
-    use std::collections::HashMap;
+    use crate::replay::{assert_round_trip, fixture_connector};
     use std::env;
 
+    #[tokio::test]
+    async fn insider_trades_from_fixture() {
+        let gf_connect = fixture_connector();
+        let trades = gf_connect.insider_trades("NAS:NVDA").await.unwrap();
+        assert_eq!(trades["NVDA"][0].insider, "Jen Hsun Huang");
+        let updates = gf_connect.insider_updates().await.unwrap();
+        assert_eq!(updates[1].symbol, "KO");
+        assert_round_trip(&trades);
+        assert_round_trip(&updates);
+    }
+
     #[tokio::test]
     async fn test_insider_trades() {
         if let Ok(token) = env::var("GURUFOCUS_TOKEN") {
@@ -57,6 +76,18 @@ mod test {
         }
     }
 
+    #[tokio::test]
+    async fn test_insider_trades_typed() {
+        if let Ok(token) = env::var("GURUFOCUS_TOKEN") {
+            if !token.is_empty() {
+                let gf_connect = GuruFocusConnector::new(token);
+                let stock = "NAS:NVDA";
+                let trades = gf_connect.insider_trades(stock).await;
+                assert!(trades.is_ok());
+            }
+        }
+    }
+
     #[tokio::test]
     async fn test_insider_updates() {
         if let Ok(token) = env::var("GURUFOCUS_TOKEN") {
@@ -69,4 +100,15 @@ mod test {
             }
         }
     }
+
+    #[tokio::test]
+    async fn test_insider_updates_typed() {
+        if let Ok(token) = env::var("GURUFOCUS_TOKEN") {
+            if !token.is_empty() {
+                let gf_connect = GuruFocusConnector::new(token);
+                let updates = gf_connect.insider_updates().await;
+                assert!(updates.is_ok());
+            }
+        }
+    }
 }
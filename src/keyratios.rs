@@ -1,11 +1,13 @@
-use serde::Deserialize;
+use crate::extra::Extra;
+use crate::GuruFocusError;
+use chrono::{NaiveDate, NaiveDateTime};
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
 pub use crate::strnum::FloatOrString;
 
 /// Structure holding all key ratios for a single stock.
-#[derive(Deserialize, Debug)]
-#[serde(deny_unknown_fields)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct KeyRatios {
     #[serde(rename = "Basic")]
     pub basic: Basic,
@@ -27,67 +29,259 @@ pub struct KeyRatios {
     pub valuation: Valuation,
     #[serde(rename = "Quality")]
     pub quality: Quality,
+    /// Fields unknown to this crate, see `FieldMode`
+    #[serde(flatten)]
+    pub extra: Extra<KeyRatios>,
 }
 
-#[derive(Deserialize, Debug)]
-#[serde(deny_unknown_fields)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct IncomeStatement {
     #[serde(rename = "Selling, General, & Admin. Expense")]
     pub selling_general_and_admin_expense: FloatOrString,
+    /// Fields unknown to this crate, see `FieldMode`
+    #[serde(flatten)]
+    pub extra: Extra<IncomeStatement>,
 }
 
-#[derive(Deserialize, Debug)]
-#[serde(deny_unknown_fields)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct Basic {
     #[serde(rename = "Price Updated Time")]
-    pub price_updated_time: String,
+    #[serde(with = "crate::dates::date_time")]
+    pub price_updated_time: NaiveDateTime,
     #[serde(rename = "Company")]
     pub company: String,
+    /// Fields unknown to this crate, see `FieldMode`
+    #[serde(flatten)]
+    pub extra: Extra<Basic>,
 }
 
-#[derive(Deserialize, Debug)]
-#[serde(deny_unknown_fields)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct Valuation {
     #[serde(rename = "Earnings Power Value (EPV)")]
     pub epv: FloatOrString,
+    /// Fields unknown to this crate, see `FieldMode`
+    #[serde(flatten)]
+    pub extra: Extra<Valuation>,
 }
 
-#[derive(Deserialize, Debug)]
-#[serde(deny_unknown_fields)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct Quality {
     #[serde(rename = "Predictability Rank")]
     pub predictability_rank: FloatOrString,
+    /// Fields unknown to this crate, see `FieldMode`
+    #[serde(flatten)]
+    pub extra: Extra<Quality>,
+}
+
+/// Sections and names of the key ratios known to this crate, as used by `KeyRatios::lookup`;
+/// the section `Basic` holds no ratios, but the company name and the time of the price
+pub const KEY_RATIO_CATALOG: &[(&str, &[&str])] = &[
+    (
+        "Fundamental",
+        &[
+            "Market Cap (M)",
+            "Enterprise Value (M)",
+            "Shares Outstanding (M)",
+            "Current Ratio",
+            "Quick Ratio",
+            "Debt-to-Equity",
+            "Debt-to-EBITDA",
+            "Interest Coverage",
+            "Altman Z-Score",
+            "Piotroski F-Score",
+            "Beneish M-Score",
+            "Book Value per Share",
+            "Tangible Book per Share",
+            "Cash per Share",
+        ],
+    ),
+    (
+        "Valuation Ratio",
+        &[
+            "PE Ratio",
+            "Forward PE Ratio",
+            "PE Ratio without NRI",
+            "PB Ratio",
+            "PS Ratio",
+            "Price-to-Free-Cash-Flow",
+            "Price-to-Tangible-Book",
+            "EV-to-EBIT",
+            "EV-to-EBITDA",
+            "EV-to-Revenue",
+            "PEG Ratio",
+            "Shiller PE Ratio",
+            "Earnings Yield (Greenblatt) %",
+        ],
+    ),
+    (
+        "Profitability",
+        &[
+            "Gross Margin %",
+            "Operating Margin %",
+            "Net Margin %",
+            "FCF Margin %",
+            "ROE %",
+            "ROA %",
+            "ROIC %",
+            "ROC (Joel Greenblatt) %",
+        ],
+    ),
+    (
+        "Growth",
+        &[
+            "3-Year Revenue Growth Rate",
+            "3-Year EPS without NRI Growth Rate",
+            "3-Year FCF Growth Rate",
+            "5-Year Revenue Growth Rate",
+            "5-Year EPS without NRI Growth Rate",
+            "5-Year FCF Growth Rate",
+            "10-Year Revenue Growth Rate",
+            "10-Year EPS without NRI Growth Rate",
+        ],
+    ),
+    (
+        "Price",
+        &[
+            "Current Price",
+            "52-Week High",
+            "52-Week Low",
+            "Beta",
+            "Volume",
+        ],
+    ),
+    (
+        "Dividends",
+        &[
+            "Dividend Yield %",
+            "Forward Dividend Yield %",
+            "Dividend Payout Ratio",
+            "3-Year Dividend Growth Rate",
+            "5-Year Yield-on-Cost %",
+        ],
+    ),
+    ("Income Statement", &["Selling, General, & Admin. Expense"]),
+    ("Valuation", &["Earnings Power Value (EPV)"]),
+    ("Quality", &["Predictability Rank"]),
+];
+
+/// Most frequently used key ratios; percentages and growth rates are given in percent,
+/// e.g. `38.5` for an ROE of 38.5%
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+pub struct CoreRatios {
+    pub pe: Option<f64>,
+    pub pb: Option<f64>,
+    pub roe: Option<f64>,
+    pub roic: Option<f64>,
+    pub debt_to_equity: Option<f64>,
+    pub dividend_yield: Option<f64>,
+    pub revenue_growth_5y: Option<f64>,
+    pub eps_growth_5y: Option<f64>,
+}
+
+impl KeyRatios {
+    /// Returns the ratio `name` of the given section as number, e.g.
+    /// `get("Profitability", "ROE %")`; returns `None` if the ratio is not available or
+    /// not a number
+    pub fn get(&self, section: &str, name: &str) -> Option<f64> {
+        self.raw(section, name)?.as_f64()
+    }
+
+    /// Like `get`, but returns an error for ratios missing in `KEY_RATIO_CATALOG`, which
+    /// catches misspelled names
+    pub fn lookup(&self, section: &str, name: &str) -> Result<Option<f64>, GuruFocusError> {
+        let known = KEY_RATIO_CATALOG
+            .iter()
+            .any(|(s, names)| *s == section && names.contains(&name));
+        if known {
+            Ok(self.get(section, name))
+        } else {
+            Err(GuruFocusError::UnknownRatio {
+                section: section.to_string(),
+                name: name.to_string(),
+            })
+        }
+    }
+
+    /// Returns the most frequently used ratios
+    pub fn core_ratios(&self) -> CoreRatios {
+        CoreRatios {
+            pe: self.get("Valuation Ratio", "PE Ratio"),
+            pb: self.get("Valuation Ratio", "PB Ratio"),
+            roe: self.get("Profitability", "ROE %"),
+            roic: self.get("Profitability", "ROIC %"),
+            debt_to_equity: self.get("Fundamental", "Debt-to-Equity"),
+            dividend_yield: self.get("Dividends", "Dividend Yield %"),
+            revenue_growth_5y: self.get("Growth", "5-Year Revenue Growth Rate"),
+            eps_growth_5y: self.get("Growth", "5-Year EPS without NRI Growth Rate"),
+        }
+    }
+
+    /// Returns the ratio `name` of the given section, regardless of whether the section
+    /// is typed or not
+    fn raw(&self, section: &str, name: &str) -> Option<FloatOrString> {
+        let value = match section {
+            "Fundamental" => self.fundamental.get(name),
+            "Valuation Ratio" => self.valuation_ratio.get(name),
+            "Profitability" => self.profitability.get(name),
+            "Growth" => self.growth.get(name),
+            "Price" => self.price.get(name),
+            "Dividends" => self.dividends.get(name),
+            "Income Statement" if name == "Selling, General, & Admin. Expense" => {
+                return Some(
+                    self.income_statement
+                        .selling_general_and_admin_expense
+                        .clone(),
+                )
+            }
+            "Income Statement" => self.income_statement.extra.get(name),
+            "Valuation" if name == "Earnings Power Value (EPV)" => {
+                return Some(self.valuation.epv.clone())
+            }
+            "Valuation" => self.valuation.extra.get(name),
+            "Quality" if name == "Predictability Rank" => {
+                return Some(self.quality.predictability_rank.clone())
+            }
+            "Quality" => self.quality.extra.get(name),
+            _ => None,
+        }?;
+        serde_json::from_value(value.clone()).ok()
+    }
 }
 
 /// Container for analyst estimates for all periods
-#[derive(Deserialize, Debug)]
-#[serde(deny_unknown_fields)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct AnalystEstimates {
     pub annual: AnnualAnalystEstimate,
     pub quarter: QuarterlyAnalystEstimate,
+    /// Fields unknown to this crate, see `FieldMode`
+    #[serde(flatten)]
+    pub extra: Extra<AnalystEstimates>,
 }
 
 /// Container for analyst estimates for annual periods
-#[derive(Deserialize, Debug)]
-#[serde(deny_unknown_fields)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct AnnualAnalystEstimate {
     pub long_term_growth_rate_mean: FloatOrString,
     pub long_term_revenue_growth_rate_mean: FloatOrString,
-    pub date: Vec<String>,
+    #[serde(with = "crate::dates::date_list")]
+    pub date: Vec<NaiveDate>,
     pub revenue_estimate: Vec<FloatOrString>,
     pub eps_nri_estimate: Vec<FloatOrString>,
     pub per_share_eps_estimate: Vec<FloatOrString>,
     pub ebit_estimate: Vec<FloatOrString>,
     pub ebitda_estimate: Vec<FloatOrString>,
     pub dividend_estimate: Vec<FloatOrString>,
+    /// Fields unknown to this crate, see `FieldMode`
+    #[serde(flatten)]
+    pub extra: Extra<AnnualAnalystEstimate>,
 }
 
 /// Container for analyst estimates for quarterly periods
-#[derive(Deserialize, Debug)]
-#[serde(deny_unknown_fields)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct QuarterlyAnalystEstimate {
     pub long_term_growth_rate_mean: FloatOrString,
-    pub date: Vec<String>,
+    #[serde(with = "crate::dates::date_list")]
+    pub date: Vec<NaiveDate>,
     pub revenue_estimate: Vec<FloatOrString>,
     pub eps_nri_estimate: Vec<FloatOrString>,
     pub per_share_eps_estimate: Vec<FloatOrString>,
@@ -95,14 +289,101 @@ pub struct QuarterlyAnalystEstimate {
     pub ebitda_estimate: Vec<FloatOrString>,
     pub dividend_estimate: Vec<FloatOrString>,
     pub pettm_estimate: Vec<FloatOrString>,
+    /// Fields unknown to this crate, see `FieldMode`
+    #[serde(flatten)]
+    pub extra: Extra<QuarterlyAnalystEstimate>,
 }
 
 #[cfg(test)]
 mod tests {
     use super::super::*;
-    use super::*;
+    use crate::replay::{assert_round_trip, fixture_connector};
     use std::env;
 
+    #[tokio::test]
+    async fn key_ratios_from_fixture() {
+        let gf_connect = fixture_connector();
+        let key_ratios = gf_connect.key_ratios("NAS:MSFT").await.unwrap();
+        assert_eq!(key_ratios.basic.company, "Microsoft Corp");
+        assert_eq!(key_ratios.valuation.epv.value(), 131.06);
+        assert_eq!(key_ratios.valuation_ratio["PE Ratio"], "34.00");
+        assert_round_trip(&key_ratios);
+    }
+
+    #[tokio::test]
+    async fn ratio_lookup() {
+        let gf_connect = fixture_connector();
+        let key_ratios = gf_connect.key_ratios("NAS:MSFT").await.unwrap();
+        assert_eq!(key_ratios.get("Profitability", "ROE %"), Some(38.49));
+        assert_eq!(
+            key_ratios.get("Valuation", "Earnings Power Value (EPV)"),
+            Some(131.06)
+        );
+        assert_eq!(key_ratios.get("Quality", "Predictability Rank"), Some(4.5));
+        assert_eq!(key_ratios.get("Dividends", "Dividend Frequency"), None);
+        assert_eq!(key_ratios.get("Profitability", "ROE"), None);
+        assert_eq!(
+            key_ratios.lookup("Fundamental", "Debt-to-Equity").unwrap(),
+            Some(0.22)
+        );
+        // known ratio not delivered for this stock
+        assert_eq!(
+            key_ratios.lookup("Fundamental", "Quick Ratio").unwrap(),
+            None
+        );
+        assert!(matches!(
+            key_ratios.lookup("Profitability", "ROE"),
+            Err(GuruFocusError::UnknownRatio { .. })
+        ));
+        assert_eq!(key_ratios.get("Basic", "Company"), None);
+        assert!(matches!(
+            key_ratios.lookup("Basic", "Company"),
+            Err(GuruFocusError::UnknownRatio { .. })
+        ));
+
+        let core = key_ratios.core_ratios();
+        assert_eq!(core.pe, Some(34.0));
+        assert_eq!(core.pb, Some(11.73));
+        assert_eq!(core.roic, Some(29.85));
+        assert_eq!(core.dividend_yield, Some(0.78));
+        assert_eq!(core.revenue_growth_5y, Some(14.3));
+        assert_eq!(core.eps_growth_5y, Some(19.1));
+    }
+
+    #[test]
+    fn catalog_covers_fixture() {
+        let json = std::fs::read_to_string(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/fixtures/stock_NAS%3AMSFT_keyratios.json"
+        ))
+        .unwrap();
+        let value: Value = serde_json::from_str(&json).unwrap();
+        assert!(KEY_RATIO_CATALOG.iter().all(|(_, names)| !names.is_empty()));
+        for (section, names) in value.as_object().unwrap() {
+            if section == "Basic" {
+                continue;
+            }
+            let known = KEY_RATIO_CATALOG
+                .iter()
+                .find(|(s, _)| s == section)
+                .map(|(_, names)| *names)
+                .unwrap();
+            for name in names.as_object().unwrap().keys() {
+                let is_text = name == "Dividend Frequency";
+                assert!(is_text || known.contains(&name.as_str()), "{}", name);
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn analyst_estimates_from_fixture() {
+        let gf_connect = fixture_connector();
+        let estimates = gf_connect.analyst_estimate("NAS:MSFT").await.unwrap();
+        assert_eq!(estimates.annual.date.len(), 3);
+        assert_eq!(estimates.quarter.pettm_estimate.len(), 3);
+        assert_round_trip(&estimates);
+    }
+
     #[tokio::test]
     async fn test_key_ratios() {
         if let Ok(token) = env::var("GURUFOCUS_TOKEN") {
@@ -120,6 +401,20 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_key_ratios_typed() {
+        if let Ok(token) = env::var("GURUFOCUS_TOKEN") {
+            if !token.is_empty() {
+                let gf_connect = GuruFocusConnector::new(token);
+
+                // Get key ratios of Berkshire Hathaway
+                let stock = "NYSE:BRK.A";
+                let key_ratios = gf_connect.key_ratios(stock).await;
+                assert!(key_ratios.is_ok());
+            }
+        }
+    }
+
     #[tokio::test]
     async fn test_analyst_estimates() {
         if let Ok(token) = env::var("GURUFOCUS_TOKEN") {
@@ -135,4 +430,17 @@ mod tests {
             }
         }
     }
+
+    #[tokio::test]
+    async fn test_analyst_estimates_typed() {
+        if let Ok(token) = env::var("GURUFOCUS_TOKEN") {
+            if !token.is_empty() {
+                let gf_connect = GuruFocusConnector::new(token);
+
+                let stock = "NAS:CSCO";
+                let estimates = gf_connect.analyst_estimate(stock).await;
+                assert!(estimates.is_ok());
+            }
+        }
+    }
 }
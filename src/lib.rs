@@ -20,28 +20,35 @@
 //! without the `.rs` extension
 //! Please note that running any of the examples increases your API access counter by at least 1.
 //!
-//! The GuruFocus API provides all data in JSON format, and the basic API functions currently
+//! The GuruFocus API provides all data in JSON format, and the basic API functions (`get_*`)
 //! will just return these JSON structures as `serde_json::Value` types without any further
-//! processing. The `serde_json::Value` types can be deserialized
-//! into more meaningful data structures, as is demonstrated in the `gurulist` example.
+//! processing. For each of these functions, there is a typed companion without the `get_`
+//! prefix (e.g. `quotes` for `get_quotes`), which deserializes the JSON response into the
+//! data structures of this crate.
 //!
 //! The GuruFocus API returns numbers sometimes as numbers, sometimes as strings. This is dealt
-//! with by introducing a new struct `FloatOrString` containing a float value, but which can
-//! be read from either a string or float automatically. The drawback is that `.0` as to be
-//! added to the variable name of a specific data structure. I.e., to access the quoted price
-//! in a variable of type Quote, i.e. `q: Quote`, the price can be accessed via `q.price.0` instead
-//! of `q.price`. In a few cases, the string contains not a number, but an error message, like
-//! "Negative Tangible Equity". In such cases, if the string can not be parsed to a number, the
-//! value is set to `NAN`.
+//! with by the type `FloatOrString`, which can be read from either a string or a number and
+//! distinguishes numeric values, percentages, missing values and texts given instead of a
+//! number, like "Negative Tangible Equity". I.e., the quoted price in a variable `q: Quote`
+//! can be accessed via `q.price.as_f64()`, which returns `None` if no number is available,
+//! or via `q.price.value()`, which returns `NAN` in this case (like the former field `.0`).
 //!
 //! Since version 0.4, all requests using the ```async``` attribute, returning a Future instead of
 //! waiting for the response and returning the result. To get the actual results, ```.await``` or ```block_on```
 //! or something similar needs to be used. The examples demonstrate how the library could be used.
+//! With the cargo feature `blocking`, the `BlockingGuruFocusConnector` provides the same requests
+//! as synchronous functions, which drive an internal runtime.
 //!
 //! Please note that the library is not yet stable and that the user interface is still subject to change.
 //! However, feedback regarding the usability and suggestions for improving the interface are welcome.
 
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use reqwest::StatusCode;
+use serde::de::DeserializeOwned;
 use serde_json::{self, Value};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
 use thiserror::Error;
 
 /// Special types for dealing with Gurus.
@@ -55,10 +62,51 @@ pub use stock::*;
 pub mod financials;
 pub use financials::*;
 
+/// Typed financial statements per industry template.
+pub mod statements;
+pub use statements::*;
+
+/// Price histories with dates.
+pub mod prices;
+pub use prices::{Drawdown, Frequency, PriceSeries};
+
+/// Corporate actions inferred from adjusted and unadjusted prices.
+pub mod corporate_actions;
+pub use corporate_actions::{
+    adjust_prices, adjust_shares, detect_corporate_actions, CorporateAction,
+};
+
+/// Analytics of the dividend history and dividend calendar.
+pub mod dividends;
+pub use dividends::{
+    dividend_events, DividendCut, DividendEvent, DividendEventKind, DividendFrequency,
+    DividendHistory,
+};
+
+/// Sustainability of dividends based on financial data.
+pub mod sustainability;
+pub use sustainability::{DividendSafety, SafetyThresholds, Verdict};
+
+/// Total return with reinvested dividends.
+pub mod total_return;
+pub use total_return::{annualized_return, total_return_index, TotalReturnOptions};
+
+/// Time series of financial data per fiscal period.
+pub mod series;
+pub use series::{FinancialSeries, SeriesEntry};
+
 /// Special types for key ratios.
 pub mod keyratios;
 pub use keyratios::*;
 
+/// Intrinsic value calculators reproducing the valuation lines of the summary chart.
+pub mod valuation;
+pub use valuation::{IntrinsicValues, ValuationAssumptions, ValuationInputs};
+
+/// Growth rates implied by the current price.
+pub mod reverse_dcf;
+pub use reverse_dcf::{ReverseDcf, SensitivityTable};
+
 /// Special types for insider tradingey ratios.
 pub mod insiders;
 pub use insiders::*;
@@ -73,16 +121,325 @@ pub mod strnum;
 /// Module for special hex num derserializer
 pub mod hexnum;
 
+/// Tolerant deserialization of dates
+pub mod dates;
+pub use dates::FiscalPeriod;
+
+/// Stock symbols with exchange prefix
+pub mod symbol;
+pub use symbol::{HasExchange, Symbol};
+
+/// Streams over paginated requests
+pub mod paging;
+pub use paging::PageOptions;
+
+/// List of API endpoints
+pub mod endpoint;
+pub use endpoint::Endpoint;
+
+/// Client-side rate limit and request accounting
+pub mod quota;
+pub use quota::RequestStats;
+use quota::{QuotaTracker, RateLimiter};
+
+/// Retry of failed requests
+pub mod retry;
+pub use retry::RetryPolicy;
+
+/// On-disk cache of responses
+pub mod cache;
+use cache::CacheErrorHandler;
+pub use cache::{CacheMode, ResponseCache};
+
+/// Recording and replay of responses
+pub mod replay;
+pub use replay::FixtureMode;
+
+/// Synchronous connector, available with feature `blocking`
+#[cfg(feature = "blocking")]
+pub mod blocking;
+#[cfg(feature = "blocking")]
+pub use blocking::BlockingGuruFocusConnector;
+
+/// Module for handling of fields unknown to the data structs
+pub mod extra;
+use extra::UnknownFieldHandler;
+pub use extra::{parse_json_with, Extra, FieldMode, UnknownField};
+
+/// Maximum number of characters of a response body kept in error messages
+const BODY_EXCERPT_LEN: usize = 200;
+
+/// Errors returned by the GuruFocus API adapter. The variants allow to distinguish
+/// between errors worth a retry (e.g. server errors), errors specific to a single
+/// request (e.g. unknown symbols) and errors affecting all requests (e.g. invalid token).
 #[derive(Error, Debug)]
 pub enum GuruFocusError {
     #[error("Request failure")]
     RequestFailure(#[from] reqwest::Error),
+    #[error("Unauthorized request '{endpoint}': {message}")]
+    Unauthorized { endpoint: String, message: String },
+    #[error("API quota exhausted for request '{endpoint}': {message}")]
+    QuotaExceeded { endpoint: String, message: String },
+    #[error("No data found for request '{endpoint}': {message}")]
+    NotFound { endpoint: String, message: String },
+    #[error("Server error (status {status}) for request '{endpoint}': {body}")]
+    ServerError {
+        endpoint: String,
+        status: u16,
+        body: String,
+    },
+    #[error("Unexpected response (status {status}) for request '{endpoint}': {body}")]
+    UnexpectedStatus {
+        endpoint: String,
+        status: u16,
+        body: String,
+    },
+    #[error("Invalid JSON returned for request '{endpoint}': {body}")]
+    InvalidJson {
+        endpoint: String,
+        body: String,
+        #[source]
+        source: serde_json::Error,
+    },
+    #[error("API error for request '{endpoint}': {message}")]
+    ApiError { endpoint: String, message: String },
+    #[error("Data does not match structure of type '{type_name}'")]
+    SchemaMismatch {
+        type_name: &'static str,
+        #[source]
+        source: serde_json::Error,
+    },
+    #[error("Request failed after {attempts} attempts")]
+    RetriesExhausted {
+        attempts: u32,
+        #[source]
+        source: Box<GuruFocusError>,
+    },
+    #[error("Request failed, server asks to retry after {retry_after:?}")]
+    RetryAfter {
+        retry_after: Duration,
+        #[source]
+        source: Box<GuruFocusError>,
+    },
+    #[error("No fixture file {file:?} for request '{endpoint}'")]
+    FixtureMissing {
+        endpoint: String,
+        file: std::path::PathBuf,
+    },
+    #[error("Failed to write fixture file for request '{endpoint}'")]
+    FixtureWrite {
+        endpoint: String,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("Invalid symbol '{symbol}': {reason}")]
+    InvalidSymbol {
+        symbol: String,
+        reason: &'static str,
+    },
+    #[error("Invalid {name} '{value}': {reason}")]
+    InvalidArgument {
+        name: &'static str,
+        value: String,
+        reason: &'static str,
+    },
+    #[error("Unsupported industry template '{template}' of financial statements")]
+    UnsupportedTemplate { template: String },
+    #[error("No metric '{name}' in section '{section}' of financial data")]
+    UnknownMetric { section: String, name: String },
+    #[error("Unknown key ratio '{name}' in section '{section}'")]
+    UnknownRatio { section: String, name: String },
+    #[error("Entry '{entry}' not found")]
+    MissingEntry { entry: String },
+    #[error("Entry '{entry}' does not match structure of type '{type_name}'")]
+    InvalidEntry {
+        entry: String,
+        type_name: &'static str,
+        #[source]
+        source: serde_json::Error,
+    },
+    #[error("No exchange rate from '{from}' to '{to}' available at {date}")]
+    MissingExchangeRate {
+        from: String,
+        to: String,
+        date: chrono::NaiveDate,
+    },
+    #[error("No cached response for request '{endpoint}' in offline mode")]
+    CacheMiss { endpoint: String },
+    #[error("Daily budget of {budget} requests exhausted, request to '{endpoint}' not sent")]
+    BudgetExhausted { endpoint: Endpoint, budget: u64 },
+    #[error("Data of type '{type_name}' contains unknown fields: {}", list_fields(.fields))]
+    UnknownFields {
+        type_name: &'static str,
+        fields: Vec<UnknownField>,
+    },
+}
+
+fn list_fields(fields: &[UnknownField]) -> String {
+    fields
+        .iter()
+        .map(|f| f.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Deserialize a JSON value returned by any of the connector functions into one of
+/// the data structs of this crate, e.g. `parse_json::<Vec<Quote>>(quotes)`.
+/// Fields unknown to the data structs are treated as error, see `parse_json_with`
+/// for a lenient alternative.
+pub fn parse_json<T: DeserializeOwned>(value: Value) -> Result<T, GuruFocusError> {
+    parse_json_with(value, FieldMode::Strict).map(|(data, _)| data)
 }
 
+/// Base URL of the public GuruFocus API
+pub const DEFAULT_BASE_URL: &str = "https://api.gurufocus.com/public/user/";
+
 /// Container for connection parameters to gurufocus server.
+/// Cloning a connector is cheap, all clones share the same connection pool.
+#[derive(Clone, Debug)]
 pub struct GuruFocusConnector {
-    url: &'static str,
+    url: String,
     user_token: String,
+    client: reqwest::Client,
+    headers: HeaderMap,
+    field_mode: FieldMode,
+    unknown_field_handler: Option<UnknownFieldHandler>,
+    rate_limiter: Option<Arc<RateLimiter>>,
+    quota: Arc<QuotaTracker>,
+    retry_policy: RetryPolicy,
+    cache: Option<ResponseCache>,
+    cache_error_handler: Option<CacheErrorHandler>,
+    fixtures: Option<FixtureMode>,
+}
+
+/// Builder for a `GuruFocusConnector` with non-default connection settings,
+/// e.g. a different server or a pre-configured `reqwest::Client`.
+#[derive(Debug)]
+pub struct GuruFocusConnectorBuilder {
+    url: String,
+    user_token: String,
+    client: Option<reqwest::Client>,
+    headers: HeaderMap,
+    field_mode: FieldMode,
+    unknown_field_handler: Option<UnknownFieldHandler>,
+    rate_limit: Option<(u32, Duration)>,
+    daily_budget: Option<u64>,
+    retry_policy: RetryPolicy,
+    cache: Option<ResponseCache>,
+    cache_error_handler: Option<CacheErrorHandler>,
+    fixtures: Option<FixtureMode>,
+}
+
+impl GuruFocusConnectorBuilder {
+    /// Use a different base URL than `DEFAULT_BASE_URL`, e.g. a local mock server or proxy.
+    /// The user token and the request path are appended to this URL.
+    pub fn base_url(mut self, url: &str) -> Self {
+        self.url = url.to_string();
+        if !self.url.ends_with('/') {
+            self.url.push('/');
+        }
+        self
+    }
+
+    /// Use a pre-built client (with custom timeouts, proxy, TLS settings, user agent etc.)
+    /// instead of a default client.
+    pub fn client(mut self, client: reqwest::Client) -> Self {
+        self.client = Some(client);
+        self
+    }
+
+    /// Add a header which is sent with every request.
+    pub fn default_header(mut self, name: HeaderName, value: HeaderValue) -> Self {
+        self.headers.insert(name, value);
+        self
+    }
+
+    /// Add a set of headers which are sent with every request.
+    pub fn default_headers(mut self, headers: HeaderMap) -> Self {
+        self.headers.extend(headers);
+        self
+    }
+
+    /// Set how the typed request functions treat fields unknown to the data structs.
+    /// The default is `FieldMode::Strict`.
+    pub fn field_mode(mut self, mode: FieldMode) -> Self {
+        self.field_mode = mode;
+        self
+    }
+
+    /// Register a callback which is called for each unknown field in lenient mode,
+    /// e.g. to write a log message.
+    pub fn on_unknown_field<F>(mut self, handler: F) -> Self
+    where
+        F: Fn(&UnknownField) + Send + Sync + 'static,
+    {
+        self.unknown_field_handler = Some(UnknownFieldHandler(std::sync::Arc::new(handler)));
+        self
+    }
+
+    /// Send at most `requests` requests within any time span of length `per`.
+    /// Requests exceeding this limit are delayed.
+    pub fn rate_limit(mut self, requests: u32, per: Duration) -> Self {
+        self.rate_limit = Some((requests, per));
+        self
+    }
+
+    /// Maximum number of requests per day (UTC). Further requests fail with
+    /// `GuruFocusError::BudgetExhausted` without being sent.
+    pub fn daily_budget(mut self, max_requests: u64) -> Self {
+        self.daily_budget = Some(max_requests);
+        self
+    }
+
+    /// Retry requests failing due to transient errors according to the given policy.
+    /// By default, requests are not retried.
+    pub fn retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = policy;
+        self
+    }
+
+    /// Cache responses on disk to save requests for rarely changing data
+    pub fn cache(mut self, cache: ResponseCache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Register a callback which is called with the request path and the error if a
+    /// response could not be written to the cache, e.g. because the cache directory is
+    /// read-only. The request itself does not fail.
+    pub fn on_cache_error<F>(mut self, handler: F) -> Self
+    where
+        F: Fn(&str, &std::io::Error) + Send + Sync + 'static,
+    {
+        self.cache_error_handler = Some(CacheErrorHandler(Arc::new(handler)));
+        self
+    }
+
+    /// Record responses to fixture files or replay fixture files instead of sending requests
+    pub fn fixtures(mut self, mode: FixtureMode) -> Self {
+        self.fixtures = Some(mode);
+        self
+    }
+
+    /// Create the connector
+    pub fn build(self) -> GuruFocusConnector {
+        GuruFocusConnector {
+            url: self.url,
+            user_token: self.user_token,
+            client: self.client.unwrap_or_default(),
+            headers: self.headers,
+            field_mode: self.field_mode,
+            unknown_field_handler: self.unknown_field_handler,
+            rate_limiter: self
+                .rate_limit
+                .map(|(requests, per)| Arc::new(RateLimiter::new(requests, per))),
+            quota: Arc::new(QuotaTracker::new(self.daily_budget)),
+            retry_policy: self.retry_policy,
+            cache: self.cache,
+            cache_error_handler: self.cache_error_handler,
+            fixtures: self.fixtures,
+        }
+    }
 }
 
 impl GuruFocusConnector {
@@ -90,63 +447,99 @@ impl GuruFocusConnector {
     /// token is the user token you get from gurufocus if you subscribe for
     /// a premium or premium plus account.
     pub fn new(token: String) -> GuruFocusConnector {
-        GuruFocusConnector {
-            url: "https://api.gurufocus.com/public/user/",
+        GuruFocusConnector::builder(token).build()
+    }
+
+    /// Returns a builder to configure a connector with non-default settings.
+    pub fn builder(token: String) -> GuruFocusConnectorBuilder {
+        GuruFocusConnectorBuilder {
+            url: DEFAULT_BASE_URL.to_string(),
             user_token: token,
+            client: None,
+            headers: HeaderMap::new(),
+            field_mode: FieldMode::default(),
+            unknown_field_handler: None,
+            rate_limit: None,
+            daily_budget: None,
+            retry_policy: RetryPolicy::no_retry(),
+            cache: None,
+            cache_error_handler: None,
+            fixtures: None,
         }
     }
 
     /// Returns the full history of financial data for stock symbol given as argument
-    pub async fn get_financials(&self, stock: &str) -> Result<Value, GuruFocusError> {
+    pub async fn get_financials(&self, stock: impl Into<Symbol>) -> Result<Value, GuruFocusError> {
+        let stock = symbol(stock)?;
         let args = format!("stock/{}/financials", stock);
-        self.send_request(args.as_str()).await
+        self.send_request(Endpoint::Financials, args.as_str()).await
     }
 
     /// Returns the current key statistic figures for stock symbol given as argument
-    pub async fn get_key_ratios(&self, stock: &str) -> Result<Value, GuruFocusError> {
+    pub async fn get_key_ratios(&self, stock: impl Into<Symbol>) -> Result<Value, GuruFocusError> {
+        let stock = symbol(stock)?;
         let args = format!("stock/{}/keyratios", stock);
-        self.send_request(args.as_str()).await
+        self.send_request(Endpoint::KeyRatios, args.as_str()).await
     }
 
     /// Returns the current quote data of a comma separated list of symbols given as argument
-    pub async fn get_quotes(&self, stocks: &[&str]) -> Result<Value, GuruFocusError> {
-        let args = format!("stock/{}/quote", compact_list(stocks));
-        self.send_request(args.as_str()).await
+    pub async fn get_quotes(
+        &self,
+        stocks: &[impl Into<Symbol> + Clone],
+    ) -> Result<Value, GuruFocusError> {
+        let args = format!("stock/{}/quote", compact_list(&symbols(stocks)?));
+        self.send_request(Endpoint::Quote, args.as_str()).await
     }
 
     /// Returns the history of (adjusted) quoted prices for symbol given as argument
-    pub async fn get_price_hist(&self, stock: &str) -> Result<Value, GuruFocusError> {
+    pub async fn get_price_hist(&self, stock: impl Into<Symbol>) -> Result<Value, GuruFocusError> {
+        let stock = symbol(stock)?;
         let args = format!("stock/{}/price", stock);
-        self.send_request(args.as_str()).await
+        self.send_request(Endpoint::Price, args.as_str()).await
     }
 
     /// Returns the history of (unadjusted) quoted prices for symbol given as argument
-    pub async fn get_unadj_price_hist(&self, stock: &str) -> Result<Value, GuruFocusError> {
+    pub async fn get_unadj_price_hist(
+        &self,
+        stock: impl Into<Symbol>,
+    ) -> Result<Value, GuruFocusError> {
+        let stock = symbol(stock)?;
         let args = format!("stock/{}/unadjusted_price", stock);
-        self.send_request(args.as_str()).await
+        self.send_request(Endpoint::UnadjustedPrice, args.as_str())
+            .await
     }
 
     /// Returns companies current price, valuation rations and ranks for symbol given as argument
-    pub async fn get_stock_summary(&self, stock: &str) -> Result<Value, GuruFocusError> {
+    pub async fn get_stock_summary(
+        &self,
+        stock: impl Into<Symbol>,
+    ) -> Result<Value, GuruFocusError> {
+        let stock = symbol(stock)?;
         let args = format!("stock/{}/summary", stock);
-        self.send_request(args.as_str()).await
+        self.send_request(Endpoint::Summary, args.as_str()).await
     }
 
     /// Returns real-time guru trades and holding data for symbol given as argument
-    pub async fn get_guru_trades(&self, stock: &str) -> Result<Value, GuruFocusError> {
+    pub async fn get_guru_trades(&self, stock: impl Into<Symbol>) -> Result<Value, GuruFocusError> {
+        let stock = symbol(stock)?;
         let args = format!("stock/{}/gurus", stock);
-        self.send_request(args.as_str()).await
+        self.send_request(Endpoint::GuruTrades, args.as_str()).await
     }
 
     /// Returns real-time insider trades for symbol given as argument
-    pub async fn get_insider_trades(&self, stock: &str) -> Result<Value, GuruFocusError> {
+    pub async fn get_insider_trades(
+        &self,
+        stock: impl Into<Symbol>,
+    ) -> Result<Value, GuruFocusError> {
+        let stock = symbol(stock)?;
         let args = format!("stock/{}/insider", stock);
-        self.send_request(args.as_str()).await
+        self.send_request(Endpoint::InsiderTrades, args.as_str())
+            .await
     }
 
     /// Returns lists of all and personalized gurus
     pub async fn get_gurus(&self) -> Result<Value, GuruFocusError> {
-        self.send_request("gurulist").await
+        self.send_request(Endpoint::GuruList, "gurulist").await
     }
 
     /// Returns list of gurus stock picks using list of guru ids since a given start date.
@@ -158,50 +551,64 @@ impl GuruFocusConnector {
     ) -> Result<Value, GuruFocusError> {
         let args = format!(
             "guru/{}/picks/{}/{}",
-            compact_list(gurus),
+            compact_list(&guru_ids(gurus)?),
             start_date.format("%F"),
             page
         );
-        self.send_request(args.as_str()).await
+        self.send_request(Endpoint::GuruPicks, args.as_str()).await
     }
 
     /// Returns list of aggregated guru portfolios given a slice of guru ids
     pub async fn get_guru_portfolios(&self, gurus: &[&str]) -> Result<Value, GuruFocusError> {
-        let args = format!("guru/{}/aggregated", compact_list(gurus));
-        self.send_request(args.as_str()).await
+        let args = format!("guru/{}/aggregated", compact_list(&guru_ids(gurus)?));
+        self.send_request(Endpoint::GuruPortfolios, args.as_str())
+            .await
     }
 
     /// Returns list of supported exchanges
     pub async fn get_exchanges(&self) -> Result<Value, GuruFocusError> {
-        self.send_request("exchange_list").await
+        self.send_request(Endpoint::ExchangeList, "exchange_list")
+            .await
     }
 
     /// Returns list of all stocks of a particular exchange
     pub async fn get_listed_stocks(&self, exchange: &str) -> Result<Value, GuruFocusError> {
-        let args = format!("exchange_stocks/{}", exchange);
-        self.send_request(args.as_str()).await
+        let args = format!("exchange_stocks/{}", path_segment("exchange", exchange)?);
+        self.send_request(Endpoint::ExchangeStocks, args.as_str())
+            .await
     }
 
     /// Returns list of latest insider trades ordered by insider transctions time
     pub async fn get_insider_updates(&self) -> Result<Value, GuruFocusError> {
-        self.send_request("insider_updates").await
+        self.send_request(Endpoint::InsiderUpdates, "insider_updates")
+            .await
     }
 
     /// Returns 30 years dividend history data of a stock
-    pub async fn get_dividend_history(&self, stock: &str) -> Result<Value, GuruFocusError> {
+    pub async fn get_dividend_history(
+        &self,
+        stock: impl Into<Symbol>,
+    ) -> Result<Value, GuruFocusError> {
+        let stock = symbol(stock)?;
         let args = format!("stock/{}/dividend", stock);
-        self.send_request(args.as_str()).await
+        self.send_request(Endpoint::Dividend, args.as_str()).await
     }
 
     /// Returns analyst estimate data of a stock
-    pub async fn get_analyst_estimate(&self, stock: &str) -> Result<Value, GuruFocusError> {
-        let args = format!("stock/{}/analyst_estimate ", stock);
-        self.send_request(args.as_str()).await
+    pub async fn get_analyst_estimate(
+        &self,
+        stock: impl Into<Symbol>,
+    ) -> Result<Value, GuruFocusError> {
+        let stock = symbol(stock)?;
+        let args = format!("stock/{}/analyst_estimate", stock);
+        self.send_request(Endpoint::AnalystEstimate, args.as_str())
+            .await
     }
 
     /// Returns list of personal portfolios
     pub async fn get_personal_portfolio(&self) -> Result<Value, GuruFocusError> {
-        self.send_request("portfolio/my_portfolios").await
+        self.send_request(Endpoint::PersonalPortfolio, "portfolio/my_portfolios")
+            .await
     }
 
     /// Returns list of all stocks with updated fundamental data within a week of the given date
@@ -210,12 +617,14 @@ impl GuruFocusConnector {
         date: chrono::NaiveDate,
     ) -> Result<Value, GuruFocusError> {
         let args = format!("funda_updated/{}", date);
-        self.send_request(args.as_str()).await
+        self.send_request(Endpoint::FundamentalsUpdated, args.as_str())
+            .await
     }
 
     /// Returns lists of politicians
     pub async fn get_politicians(&self) -> Result<Value, GuruFocusError> {
-        self.send_request("politicians").await
+        self.send_request(Endpoint::Politicians, "politicians")
+            .await
     }
 
     // Returns list of latest politician transactions
@@ -231,19 +640,420 @@ impl GuruFocusConnector {
                 Some(at) => format!("{at:?}"),
             }
         );
-        self.send_request(args.as_str()).await
+        self.send_request(Endpoint::PoliticianTransactions, args.as_str())
+            .await
+    }
+
+    /// Returns the number of requests sent so far by this connector and all its clones
+    pub fn request_stats(&self) -> RequestStats {
+        self.quota.stats()
+    }
+
+    /// Returns the response cache, e.g. to invalidate entries
+    pub fn cache(&self) -> Option<&ResponseCache> {
+        self.cache.as_ref()
+    }
+
+    /// Send request to gurufocus server and transform response to JSON value.
+    /// In replay mode, the response is read from the fixture file instead.
+    async fn send_request(&self, endpoint: Endpoint, args: &str) -> Result<Value, GuruFocusError> {
+        match &self.fixtures {
+            Some(fixtures @ FixtureMode::Replay(_)) => fixtures.load(args),
+            Some(fixtures @ FixtureMode::Record(_)) => {
+                let value = self.send_cached(endpoint, args).await?;
+                fixtures
+                    .store(args, &value, &self.user_token)
+                    .map_err(|source| GuruFocusError::FixtureWrite {
+                        endpoint: args.to_string(),
+                        source,
+                    })?;
+                Ok(value)
+            }
+            None => self.send_cached(endpoint, args).await,
+        }
+    }
+
+    /// Send request, unless a valid response is found in the cache
+    async fn send_cached(&self, endpoint: Endpoint, args: &str) -> Result<Value, GuruFocusError> {
+        let cache = match &self.cache {
+            Some(cache) => cache,
+            None => return self.send_with_retry(endpoint, args).await,
+        };
+        if let Some(value) = cache.get(endpoint, args) {
+            return Ok(value);
+        }
+        if cache.cache_mode() == CacheMode::Offline {
+            return Err(GuruFocusError::CacheMiss {
+                endpoint: args.to_string(),
+            });
+        }
+        let value = self.send_with_retry(endpoint, args).await?;
+        // failing to write the cache must not fail the request, but is reported
+        if let Err(err) = cache.put(endpoint, args, &value) {
+            if let Some(handler) = &self.cache_error_handler {
+                (handler.0)(args, &err);
+            }
+        }
+        Ok(value)
+    }
+
+    /// Send request and retry on transient errors according to the retry policy
+    async fn send_with_retry(
+        &self,
+        endpoint: Endpoint,
+        args: &str,
+    ) -> Result<Value, GuruFocusError> {
+        let mut attempt = 1;
+        loop {
+            let (result, retry_after) = self.send_once(endpoint, args).await;
+            match result {
+                Ok(value) => return Ok(value),
+                Err(err)
+                    if attempt < self.retry_policy.max_attempts
+                        && retry::is_transient(&err, retry_after) =>
+                {
+                    if let Some(retry_after) = self.retry_policy.excessive_retry_after(retry_after)
+                    {
+                        return Err(GuruFocusError::RetryAfter {
+                            retry_after,
+                            source: Box::new(err),
+                        });
+                    }
+                    tokio::time::sleep(self.retry_policy.delay(attempt, retry_after)).await;
+                    attempt += 1;
+                }
+                // errors that are not worth retrying, like an exhausted daily budget,
+                // are passed on as they are even after earlier attempts failed
+                Err(err) if attempt > 1 && retry::is_transient(&err, retry_after) => {
+                    return Err(GuruFocusError::RetriesExhausted {
+                        attempts: attempt,
+                        source: Box::new(err),
+                    })
+                }
+                Err(err) => return Err(err),
+            }
+        }
     }
 
-    /// Send request to gurufocus server and transform response to JSON value
-    async fn send_request(&self, args: &str) -> Result<Value, GuruFocusError> {
+    /// Send a single request and return the result together with the delay
+    /// requested by the server before sending another request, if any.
+    async fn send_once(
+        &self,
+        endpoint: Endpoint,
+        args: &str,
+    ) -> (Result<Value, GuruFocusError>, Option<Duration>) {
+        // fail fast without using up a token once the daily budget is exhausted
+        if let Err(err) = self.quota.register(endpoint) {
+            return (Err(err), None);
+        }
+        if let Some(rate_limiter) = &self.rate_limiter {
+            rate_limiter.acquire().await;
+        }
         let url: String = format!("{}{}/{}", self.url, self.user_token, args);
-        let resp = reqwest::get(url.as_str()).await?;
-        Ok(resp.json().await?)
+        let resp = match self
+            .client
+            .get(url.as_str())
+            .headers(self.headers.clone())
+            .send()
+            .await
+        {
+            Ok(resp) => resp,
+            Err(err) => return (Err(err.into()), None),
+        };
+        let status = resp.status();
+        let retry_after = retry::parse_retry_after(resp.headers());
+        let result = match resp.text().await {
+            Ok(body) => check_response(args, status, &body),
+            Err(err) => Err(err.into()),
+        };
+        (result, retry_after)
+    }
+
+    /// Deserialize response into data struct according to the configured field mode
+    fn parse<T: DeserializeOwned>(&self, value: Value) -> Result<T, GuruFocusError> {
+        let (data, unknown) = parse_json_with(value, self.field_mode)?;
+        if let Some(handler) = &self.unknown_field_handler {
+            unknown.iter().for_each(|field| (handler.0)(field));
+        }
+        Ok(data)
+    }
+}
+
+/// Typed versions of the requests above, which deserialize the JSON response
+/// into the data structs of this crate.
+impl GuruFocusConnector {
+    /// Returns the full history of financial data for stock symbol given as argument
+    pub async fn financials(
+        &self,
+        stock: impl Into<Symbol>,
+    ) -> Result<FinancialData, GuruFocusError> {
+        self.parse(self.get_financials(stock).await?)
+    }
+
+    /// Returns the current key statistic figures for stock symbol given as argument
+    pub async fn key_ratios(&self, stock: impl Into<Symbol>) -> Result<KeyRatios, GuruFocusError> {
+        self.parse(self.get_key_ratios(stock).await?)
+    }
+
+    /// Returns the current quote data of a list of symbols given as argument
+    pub async fn quotes(
+        &self,
+        stocks: &[impl Into<Symbol> + Clone],
+    ) -> Result<Vec<Quote>, GuruFocusError> {
+        self.parse(self.get_quotes(stocks).await?)
+    }
+
+    /// Returns the history of (adjusted) quoted prices for symbol given as argument
+    pub async fn price_hist(
+        &self,
+        stock: impl Into<Symbol>,
+    ) -> Result<PriceSeries, GuruFocusError> {
+        self.parse(self.get_price_hist(stock).await?)
+    }
+
+    /// Returns the history of (unadjusted) quoted prices for symbol given as argument
+    pub async fn unadj_price_hist(
+        &self,
+        stock: impl Into<Symbol>,
+    ) -> Result<PriceSeries, GuruFocusError> {
+        self.parse(self.get_unadj_price_hist(stock).await?)
+    }
+
+    /// Returns companies current price, valuation rations and ranks for symbol given as argument
+    pub async fn stock_summary(
+        &self,
+        stock: impl Into<Symbol>,
+    ) -> Result<StockSummary, GuruFocusError> {
+        self.parse(self.get_stock_summary(stock).await?)
+    }
+
+    /// Returns real-time guru trades and holding data for symbol given as argument
+    pub async fn guru_trades(
+        &self,
+        stock: impl Into<Symbol>,
+    ) -> Result<HashMap<String, gurus::GuruTrades>, GuruFocusError> {
+        self.parse(self.get_guru_trades(stock).await?)
+    }
+
+    /// Returns real-time insider trades for symbol given as argument
+    pub async fn insider_trades(
+        &self,
+        stock: impl Into<Symbol>,
+    ) -> Result<HashMap<String, Vec<InsiderTrade>>, GuruFocusError> {
+        self.parse(self.get_insider_trades(stock).await?)
+    }
+
+    /// Returns lists of all and personalized gurus
+    pub async fn gurus(&self) -> Result<gurus::Gurus, GuruFocusError> {
+        self.parse(self.get_gurus().await?)
+    }
+
+    /// Returns the stock picks per guru id using list of guru ids since a given start date.
+    pub async fn guru_picks(
+        &self,
+        gurus: &[&str],
+        start_date: chrono::NaiveDate,
+        page: i32,
+    ) -> Result<HashMap<String, gurus::GuruPicks>, GuruFocusError> {
+        self.parse(self.get_guru_picks(gurus, start_date, page).await?)
+    }
+
+    /// Returns aggregated guru portfolios per guru id given a slice of guru ids
+    pub async fn guru_portfolios(
+        &self,
+        gurus: &[&str],
+    ) -> Result<HashMap<String, gurus::GuruPortfolio>, GuruFocusError> {
+        self.parse(self.get_guru_portfolios(gurus).await?)
+    }
+
+    /// Returns list of supported exchanges per country
+    pub async fn exchanges(&self) -> Result<HashMap<String, Vec<String>>, GuruFocusError> {
+        self.parse(self.get_exchanges().await?)
+    }
+
+    /// Returns list of all stocks of a particular exchange
+    pub async fn listed_stocks(&self, exchange: &str) -> Result<Vec<Stock>, GuruFocusError> {
+        self.parse(self.get_listed_stocks(exchange).await?)
+    }
+
+    /// Returns list of latest insider trades ordered by insider transctions time
+    pub async fn insider_updates(&self) -> Result<Vec<InsiderUpdate>, GuruFocusError> {
+        self.parse(self.get_insider_updates().await?)
+    }
+
+    /// Returns 30 years dividend history data of a stock
+    pub async fn dividend_history(
+        &self,
+        stock: impl Into<Symbol>,
+    ) -> Result<Vec<Dividend>, GuruFocusError> {
+        self.parse(self.get_dividend_history(stock).await?)
+    }
+
+    /// Returns analyst estimate data of a stock
+    pub async fn analyst_estimate(
+        &self,
+        stock: impl Into<Symbol>,
+    ) -> Result<AnalystEstimates, GuruFocusError> {
+        self.parse(self.get_analyst_estimate(stock).await?)
+    }
+
+    /// Returns list of personal portfolios
+    pub async fn personal_portfolio(&self) -> Result<Vec<Portfolio>, GuruFocusError> {
+        self.parse(self.get_personal_portfolio().await?)
+    }
+
+    /// Returns list of all stocks with updated fundamental data within a week of the given date
+    pub async fn updated_stocks(
+        &self,
+        date: chrono::NaiveDate,
+    ) -> Result<Vec<Symbol>, GuruFocusError> {
+        self.parse(self.get_updated_stocks(date).await?)
+    }
+
+    /// Returns lists of politicians
+    pub async fn politicians(&self) -> Result<Vec<gurus::Politician>, GuruFocusError> {
+        self.parse(self.get_politicians().await?)
+    }
+
+    /// Returns a page of the list of latest politician transactions
+    pub async fn politician_transactions(
+        &self,
+        page: u32,
+        asset_type: Option<gurus::AssetType>,
+    ) -> Result<gurus::PoliticianTransactionList, GuruFocusError> {
+        self.parse(self.get_politician_transactions(page, asset_type).await?)
+    }
+}
+
+/// Convert the raw response into a JSON value, or into the error matching the
+/// HTTP status or the error message embedded in the JSON response.
+fn check_response(endpoint: &str, status: StatusCode, body: &str) -> Result<Value, GuruFocusError> {
+    let endpoint = endpoint.to_string();
+    if !status.is_success() {
+        let message = error_message(body).unwrap_or_else(|| excerpt(body));
+        return Err(match status {
+            StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => {
+                GuruFocusError::Unauthorized { endpoint, message }
+            }
+            StatusCode::TOO_MANY_REQUESTS => GuruFocusError::QuotaExceeded { endpoint, message },
+            StatusCode::NOT_FOUND => GuruFocusError::NotFound { endpoint, message },
+            _ if status.is_server_error() => GuruFocusError::ServerError {
+                endpoint,
+                status: status.as_u16(),
+                body: excerpt(body),
+            },
+            _ => GuruFocusError::UnexpectedStatus {
+                endpoint,
+                status: status.as_u16(),
+                body: excerpt(body),
+            },
+        });
+    }
+    let value: Value =
+        serde_json::from_str(body).map_err(|source| GuruFocusError::InvalidJson {
+            endpoint: endpoint.clone(),
+            body: excerpt(body),
+            source,
+        })?;
+    match in_band_error(&value) {
+        Some(message) => Err(classify_error_message(endpoint, message)),
+        None => Ok(value),
+    }
+}
+
+/// GuruFocus reports some errors with status 200 and a body like `{"error": "..."}`,
+/// possibly accompanied by further keys like `"code"`; the error may also be given as
+/// object with a `"message"`
+fn in_band_error(value: &Value) -> Option<String> {
+    match value.as_object()?.get("error")? {
+        Value::String(message) => Some(message.clone()),
+        error @ Value::Object(obj) => Some(match obj.get("message") {
+            Some(Value::String(message)) => message.clone(),
+            _ => error.to_string(),
+        }),
+        _ => None,
+    }
+}
+
+/// Extract error message from body of a failed request, if any
+fn error_message(body: &str) -> Option<String> {
+    serde_json::from_str::<Value>(body)
+        .ok()
+        .and_then(|value| in_band_error(&value))
+}
+
+/// Map an error message sent by GuruFocus to the appropriate error variant
+fn classify_error_message(endpoint: String, message: String) -> GuruFocusError {
+    let lower = message.to_lowercase();
+    let contains_any = |words: &[&str]| words.iter().any(|w| lower.contains(w));
+    if contains_any(&["rate limit", "quota", "exceed"]) {
+        GuruFocusError::QuotaExceeded { endpoint, message }
+    } else if contains_any(&["token", "unauthori", "permission", "subscri", "premium"]) {
+        GuruFocusError::Unauthorized { endpoint, message }
+    } else if contains_any(&[
+        "not found",
+        "invalid symbol",
+        "unknown",
+        "no data",
+        "not exist",
+    ]) {
+        GuruFocusError::NotFound { endpoint, message }
+    } else {
+        GuruFocusError::ApiError { endpoint, message }
+    }
+}
+
+/// Shorten response body to a size suitable for error messages
+fn excerpt(body: &str) -> String {
+    let body = body.trim();
+    match body.char_indices().nth(BODY_EXCERPT_LEN) {
+        Some((idx, _)) => format!("{}...", &body[..idx]),
+        None => body.to_string(),
+    }
+}
+
+/// Convert symbol given in any supported representation and check that it can be
+/// used in the request path
+fn symbol(stock: impl Into<Symbol>) -> Result<Symbol, GuruFocusError> {
+    stock.into().validated()
+}
+
+/// Convert list of symbols given in any supported representation and check them
+fn symbols(stocks: &[impl Into<Symbol> + Clone]) -> Result<Vec<Symbol>, GuruFocusError> {
+    stocks.iter().cloned().map(symbol).collect()
+}
+
+/// Check that an identifier, e.g. an exchange, can be used as part of a request path
+fn path_segment<'a>(name: &'static str, value: &'a str) -> Result<&'a str, GuruFocusError> {
+    let is_valid = |c: char| c.is_ascii_alphanumeric() || matches!(c, '-' | '_');
+    let reason = if value.is_empty() {
+        "empty value"
+    } else if !value.chars().all(is_valid) {
+        "invalid character"
+    } else {
+        return Ok(value);
+    };
+    Err(GuruFocusError::InvalidArgument {
+        name,
+        value: value.to_string(),
+        reason,
+    })
+}
+
+/// Check a non-empty list of guru ids
+fn guru_ids<'a>(gurus: &[&'a str]) -> Result<Vec<&'a str>, GuruFocusError> {
+    if gurus.is_empty() {
+        return Err(GuruFocusError::InvalidArgument {
+            name: "guru ids",
+            value: String::new(),
+            reason: "empty list",
+        });
     }
+    gurus.iter().map(|id| path_segment("guru id", id)).collect()
 }
 
 /// Compact list as input to url
-fn compact_list(a: &[&str]) -> String {
+fn compact_list<T: std::fmt::Display>(a: &[T]) -> String {
     if a.is_empty() {
         return String::new();
     }
@@ -255,9 +1065,24 @@ fn compact_list(a: &[&str]) -> String {
     res
 }
 
+/// Helpers shared by the unit tests of several modules
+#[cfg(test)]
+mod test_util {
+    use chrono::NaiveDate;
+
+    /// Date of the given year, month and day, which must be valid
+    pub(crate) fn ymd(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    /// Check that two numbers differ by less than the given tolerance
+    pub(crate) fn assert_close(x: f64, y: f64, tolerance: f64) {
+        assert!((x - y).abs() < tolerance, "{} != {}", x, y);
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use std::collections::HashMap;
     use std::env;
 
     use super::*;
@@ -266,7 +1091,7 @@ mod tests {
     #[test]
     fn test_compact_list() {
         assert_eq!(compact_list(&["1", "2", "3"]), "1,2,3");
-        assert_eq!(compact_list(&[]), "");
+        assert_eq!(compact_list::<&str>(&[]), "");
         assert_eq!(compact_list(&["3"]), "3");
     }
 
@@ -298,6 +1123,501 @@ mod tests {
         assert!(s.is_err());
     }
 
+    #[tokio::test]
+    async fn test_custom_base_url_and_headers() {
+        use wiremock::matchers::{header, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/my_token/exchange_list"))
+            .and(header("x-api-client", "test"))
+            .and(header("user-agent", "gurufocus-test"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(r#"{"USA": ["NAS", "NYSE"]}"#))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = reqwest::Client::builder()
+            .user_agent("gurufocus-test")
+            .build()
+            .unwrap();
+        let gf_connect = GuruFocusConnector::builder("my_token".to_string())
+            .base_url(&format!("{}/api", server.uri()))
+            .client(client)
+            .default_header(
+                HeaderName::from_static("x-api-client"),
+                HeaderValue::from_static("test"),
+            )
+            .build();
+        let exchanges = gf_connect.get_exchanges().await.unwrap();
+        let exchange_map = serde_json::from_value::<HashMap<String, Vec<String>>>(exchanges);
+        assert_eq!(exchange_map.unwrap()["USA"], vec!["NAS", "NYSE"]);
+    }
+
+    /// Connector sending requests with token "tok" to the mock server
+    fn mock_connector(server: &wiremock::MockServer) -> GuruFocusConnector {
+        GuruFocusConnector::builder("tok".to_string())
+            .base_url(&server.uri())
+            .build()
+    }
+
+    async fn mock_response(
+        server: &wiremock::MockServer,
+        request_path: &str,
+        response: wiremock::ResponseTemplate,
+    ) {
+        use wiremock::matchers::path;
+        use wiremock::Mock;
+
+        Mock::given(path(request_path))
+            .respond_with(response)
+            .mount(server)
+            .await;
+    }
+
+    #[tokio::test]
+    async fn test_http_status_errors() {
+        use wiremock::{MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        mock_response(&server, "/tok/stock/A/quote", ResponseTemplate::new(401)).await;
+        mock_response(&server, "/tok/stock/B/quote", ResponseTemplate::new(429)).await;
+        mock_response(&server, "/tok/stock/C/quote", ResponseTemplate::new(404)).await;
+        mock_response(
+            &server,
+            "/tok/stock/D/quote",
+            ResponseTemplate::new(503).set_body_string("<html>Down for maintenance</html>"),
+        )
+        .await;
+        let gf_connect = mock_connector(&server);
+
+        let err = gf_connect.get_quotes(&["A"]).await.unwrap_err();
+        assert!(matches!(err, GuruFocusError::Unauthorized { .. }));
+        let err = gf_connect.get_quotes(&["B"]).await.unwrap_err();
+        assert!(matches!(err, GuruFocusError::QuotaExceeded { .. }));
+        let err = gf_connect.get_quotes(&["C"]).await.unwrap_err();
+        assert!(
+            matches!(err, GuruFocusError::NotFound { endpoint, .. } if endpoint == "stock/C/quote")
+        );
+        let err = gf_connect.get_quotes(&["D"]).await.unwrap_err();
+        match err {
+            GuruFocusError::ServerError { status, body, .. } => {
+                assert_eq!(status, 503);
+                assert!(body.contains("maintenance"));
+            }
+            _ => panic!("unexpected error {:?}", err),
+        }
+    }
+
+    #[test]
+    fn test_in_band_error() {
+        let error = |body: &str| in_band_error(&serde_json::from_str(body).unwrap());
+        assert_eq!(
+            error(r#"{"error": "Invalid token"}"#).unwrap(),
+            "Invalid token"
+        );
+        assert_eq!(
+            error(r#"{"error": "Forbidden", "code": 403, "message": "Invalid token"}"#).unwrap(),
+            "Forbidden"
+        );
+        assert_eq!(
+            error(r#"{"error": {"code": 429, "message": "Quota exceeded"}}"#).unwrap(),
+            "Quota exceeded"
+        );
+        assert_eq!(error(r#"{"error": null, "data": []}"#), None);
+        assert_eq!(error(r#"{"error": 0, "data": []}"#), None);
+        assert_eq!(error(r#"[{"error": "text"}]"#), None);
+
+        let classify = |message: &str| classify_error_message(String::new(), message.to_string());
+        assert!(matches!(
+            classify("API rate limit reached"),
+            GuruFocusError::QuotaExceeded { .. }
+        ));
+        assert!(matches!(
+            classify("Limit of 100 symbols per request"),
+            GuruFocusError::ApiError { .. }
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_in_band_errors() {
+        use wiremock::{MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        let error_body = |msg: &str| {
+            ResponseTemplate::new(200).set_body_string(format!(r#"{{"error": "{}"}}"#, msg))
+        };
+        mock_response(&server, "/tok/stock/A/summary", error_body("Invalid token")).await;
+        mock_response(
+            &server,
+            "/tok/stock/B/summary",
+            error_body("Quota exceeded"),
+        )
+        .await;
+        mock_response(
+            &server,
+            "/tok/stock/C/summary",
+            error_body("Stock not found"),
+        )
+        .await;
+        mock_response(
+            &server,
+            "/tok/stock/D/summary",
+            error_body("Something else"),
+        )
+        .await;
+        mock_response(
+            &server,
+            "/tok/stock/E/summary",
+            ResponseTemplate::new(200).set_body_string("<html>maintenance</html>"),
+        )
+        .await;
+        let gf_connect = mock_connector(&server);
+
+        let err = gf_connect.get_stock_summary("A").await.unwrap_err();
+        assert!(matches!(err, GuruFocusError::Unauthorized { .. }));
+        let err = gf_connect.get_stock_summary("B").await.unwrap_err();
+        assert!(matches!(err, GuruFocusError::QuotaExceeded { .. }));
+        let err = gf_connect.get_stock_summary("C").await.unwrap_err();
+        assert!(matches!(err, GuruFocusError::NotFound { .. }));
+        let err = gf_connect.get_stock_summary("D").await.unwrap_err();
+        assert!(
+            matches!(err, GuruFocusError::ApiError { message, .. } if message == "Something else")
+        );
+        let err = gf_connect.get_stock_summary("E").await.unwrap_err();
+        assert!(
+            matches!(err, GuruFocusError::InvalidJson { endpoint, .. } if endpoint == "stock/E/summary")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_typed_requests() {
+        use wiremock::{MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        mock_response(
+            &server,
+            "/tok/exchange_list",
+            ResponseTemplate::new(200).set_body_string(r#"{"Norway": ["OSL"]}"#),
+        )
+        .await;
+        mock_response(
+            &server,
+            "/tok/exchange_stocks/OSL",
+            ResponseTemplate::new(200).set_body_string(r#"[{"symbol": "EQNR"}]"#),
+        )
+        .await;
+        let gf_connect = mock_connector(&server);
+
+        let exchanges = gf_connect.exchanges().await.unwrap();
+        assert_eq!(exchanges["Norway"], vec!["OSL"]);
+        let err = gf_connect.listed_stocks("OSL").await.unwrap_err();
+        assert!(
+            matches!(err, GuruFocusError::SchemaMismatch { type_name, .. } if type_name.contains("Stock"))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_invalid_symbols_not_sent() {
+        use wiremock::MockServer;
+
+        let server = MockServer::start().await;
+        let gf_connect = mock_connector(&server);
+        for stock in ["AAPL,MSFT", "AAPL?x=1", "NAS:AA PL", "AAPL/../gurulist", ""] {
+            let err = gf_connect.get_stock_summary(stock).await.unwrap_err();
+            assert!(matches!(err, GuruFocusError::InvalidSymbol { .. }));
+        }
+        let err = gf_connect
+            .get_quotes(&["AAPL", "MSFT,GOOG"])
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(err, GuruFocusError::InvalidSymbol { symbol, .. } if symbol == "MSFT,GOOG")
+        );
+        for gurus in [&["7", "16/aggregated?x="][..], &["7,16"], &[]] {
+            let err = gf_connect.get_guru_portfolios(gurus).await.unwrap_err();
+            assert!(matches!(err, GuruFocusError::InvalidArgument { .. }));
+        }
+        let start = chrono::NaiveDate::from_ymd_opt(2023, 1, 1).unwrap();
+        let err = gf_connect
+            .get_guru_picks(&["../gurulist"], start, 1)
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(err, GuruFocusError::InvalidArgument { name, value, .. } if name == "guru id" && value == "../gurulist")
+        );
+        for exchange in ["", "OSL/../x", "NAS?page=2"] {
+            let err = gf_connect.get_listed_stocks(exchange).await.unwrap_err();
+            assert!(matches!(err, GuruFocusError::InvalidArgument { .. }));
+        }
+        assert!(server.received_requests().await.unwrap().is_empty());
+        assert_eq!(gf_connect.request_stats().total, 0);
+    }
+
+    #[tokio::test]
+    async fn test_lenient_field_mode() {
+        use std::sync::{Arc, Mutex};
+        use wiremock::{MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        mock_response(
+            &server,
+            "/tok/gurulist",
+            ResponseTemplate::new(200).set_body_string(r#"{"all": {}, "my": {}, "new": true}"#),
+        )
+        .await;
+        let strict_connect = mock_connector(&server);
+        let err = strict_connect.gurus().await.unwrap_err();
+        assert!(matches!(err, GuruFocusError::UnknownFields { .. }));
+
+        let reported = Arc::new(Mutex::new(Vec::new()));
+        let reported_clone = reported.clone();
+        let lenient_connect = GuruFocusConnector::builder("tok".to_string())
+            .base_url(&server.uri())
+            .field_mode(FieldMode::Lenient)
+            .on_unknown_field(move |field| reported_clone.lock().unwrap().push(field.to_string()))
+            .build();
+        let gurus = lenient_connect.gurus().await.unwrap();
+        assert_eq!(gurus.extra["new"], Value::Bool(true));
+        assert_eq!(*reported.lock().unwrap(), vec!["Gurus.new".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_request_stats_and_budget() {
+        use wiremock::{MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        mock_response(
+            &server,
+            "/tok/exchange_list",
+            ResponseTemplate::new(200).set_body_string("{}"),
+        )
+        .await;
+        let gf_connect = GuruFocusConnector::builder("tok".to_string())
+            .base_url(&server.uri())
+            .daily_budget(2)
+            .rate_limit(2, Duration::from_secs(3600))
+            .build();
+        let clone = gf_connect.clone();
+        assert!(gf_connect.get_exchanges().await.is_ok());
+        assert!(clone.get_exchanges().await.is_ok());
+        // no waiting for the next token of the rate limiter
+        let err = tokio::time::timeout(Duration::from_secs(1), gf_connect.get_exchanges())
+            .await
+            .unwrap()
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            GuruFocusError::BudgetExhausted { budget: 2, .. }
+        ));
+
+        let stats = clone.request_stats();
+        assert_eq!(stats.total, 2);
+        assert_eq!(stats.per_endpoint[&Endpoint::ExchangeList], 2);
+        assert_eq!(server.received_requests().await.unwrap().len(), 2);
+    }
+
+    fn retry_connector(server: &wiremock::MockServer) -> GuruFocusConnector {
+        GuruFocusConnector::builder("tok".to_string())
+            .base_url(&server.uri())
+            .retry_policy(RetryPolicy {
+                max_attempts: 3,
+                initial_delay: Duration::from_millis(10),
+                ..Default::default()
+            })
+            .build()
+    }
+
+    #[tokio::test]
+    async fn test_retry_transient_errors() {
+        use wiremock::matchers::path;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(path("/tok/exchange_list"))
+            .respond_with(ResponseTemplate::new(502))
+            .up_to_n_times(1)
+            .mount(&server)
+            .await;
+        Mock::given(path("/tok/exchange_list"))
+            .respond_with(ResponseTemplate::new(429).insert_header("Retry-After", "0"))
+            .up_to_n_times(1)
+            .mount(&server)
+            .await;
+        mock_response(
+            &server,
+            "/tok/exchange_list",
+            ResponseTemplate::new(200).set_body_string(r#"{"Norway": ["OSL"]}"#),
+        )
+        .await;
+        let gf_connect = retry_connector(&server);
+        let exchanges = gf_connect.exchanges().await.unwrap();
+        assert_eq!(exchanges["Norway"], vec!["OSL"]);
+        assert_eq!(gf_connect.request_stats().total, 3);
+    }
+
+    #[tokio::test]
+    async fn test_retries_exhausted() {
+        use wiremock::{MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        mock_response(&server, "/tok/gurulist", ResponseTemplate::new(503)).await;
+        mock_response(&server, "/tok/politicians", ResponseTemplate::new(404)).await;
+        let gf_connect = retry_connector(&server);
+
+        let err = gf_connect.get_gurus().await.unwrap_err();
+        match err {
+            GuruFocusError::RetriesExhausted { attempts, source } => {
+                assert_eq!(attempts, 3);
+                assert!(matches!(
+                    *source,
+                    GuruFocusError::ServerError { status: 503, .. }
+                ));
+            }
+            _ => panic!("unexpected error {:?}", err),
+        }
+        // not retried
+        let err = gf_connect.get_politicians().await.unwrap_err();
+        assert!(matches!(err, GuruFocusError::NotFound { .. }));
+        assert_eq!(server.received_requests().await.unwrap().len(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_budget_exhausted_between_retries() {
+        use wiremock::{MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        mock_response(&server, "/tok/gurulist", ResponseTemplate::new(503)).await;
+        let gf_connect = GuruFocusConnector::builder("tok".to_string())
+            .base_url(&server.uri())
+            .retry_policy(RetryPolicy {
+                max_attempts: 3,
+                initial_delay: Duration::from_millis(10),
+                ..Default::default()
+            })
+            .daily_budget(2)
+            .build();
+
+        let err = gf_connect.get_gurus().await.unwrap_err();
+        assert!(
+            matches!(err, GuruFocusError::BudgetExhausted { budget: 2, .. }),
+            "unexpected error {:?}",
+            err
+        );
+        assert_eq!(server.received_requests().await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_retry_after_exceeding_max_delay() {
+        use wiremock::{MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        mock_response(
+            &server,
+            "/tok/gurulist",
+            ResponseTemplate::new(429).insert_header("Retry-After", "86400"),
+        )
+        .await;
+        let gf_connect = retry_connector(&server);
+
+        let start = std::time::Instant::now();
+        let err = gf_connect.get_gurus().await.unwrap_err();
+        assert!(start.elapsed() < Duration::from_secs(5));
+        match err {
+            GuruFocusError::RetryAfter {
+                retry_after,
+                source,
+            } => {
+                assert_eq!(retry_after, Duration::from_secs(86400));
+                assert!(matches!(*source, GuruFocusError::QuotaExceeded { .. }));
+            }
+            _ => panic!("unexpected error {:?}", err),
+        }
+        assert_eq!(server.received_requests().await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_response_cache() {
+        use wiremock::{MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        mock_response(
+            &server,
+            "/tok/exchange_list",
+            ResponseTemplate::new(200).set_body_string(r#"{"Norway": ["OSL"]}"#),
+        )
+        .await;
+        let dir = tempfile::tempdir().unwrap();
+        let gf_connect = GuruFocusConnector::builder("tok".to_string())
+            .base_url(&server.uri())
+            .cache(ResponseCache::new(dir.path()))
+            .build();
+        let exchanges = gf_connect.exchanges().await.unwrap();
+        let cached_exchanges = gf_connect.exchanges().await.unwrap();
+        assert_eq!(exchanges, cached_exchanges);
+        assert_eq!(gf_connect.request_stats().total, 1);
+
+        // the token must not be part of the cache
+        let offline_connect = GuruFocusConnector::builder("other".to_string())
+            .base_url(&server.uri())
+            .cache(ResponseCache::new(dir.path()).mode(CacheMode::Offline))
+            .build();
+        assert_eq!(offline_connect.exchanges().await.unwrap(), exchanges);
+        let err = offline_connect.get_gurus().await.unwrap_err();
+        assert!(matches!(err, GuruFocusError::CacheMiss { .. }));
+        assert_eq!(offline_connect.request_stats().total, 0);
+
+        let cache = gf_connect.cache().unwrap();
+        cache
+            .invalidate(Endpoint::ExchangeList, "exchange_list")
+            .unwrap();
+        gf_connect.exchanges().await.unwrap();
+        assert_eq!(server.received_requests().await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_cache_write_error() {
+        use std::sync::Mutex;
+        use wiremock::{MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        mock_response(
+            &server,
+            "/tok/exchange_list",
+            ResponseTemplate::new(200).set_body_string(r#"{"Norway": ["OSL"]}"#),
+        )
+        .await;
+        // a file in place of the cache directory makes every write fail
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("no_dir");
+        std::fs::write(&file, "").unwrap();
+        let reported = Arc::new(Mutex::new(Vec::new()));
+        let reported_clone = reported.clone();
+        let gf_connect = GuruFocusConnector::builder("tok".to_string())
+            .base_url(&server.uri())
+            .cache(ResponseCache::new(&file))
+            .on_cache_error(move |path, _| reported_clone.lock().unwrap().push(path.to_string()))
+            .build();
+        let exchanges = gf_connect.exchanges().await.unwrap();
+        assert_eq!(exchanges["Norway"], vec!["OSL"]);
+        assert_eq!(*reported.lock().unwrap(), vec!["exchange_list"]);
+    }
+
+    #[test]
+    fn test_schema_mismatch() {
+        let value: Value = serde_json::from_str(r#"{"value": "no number"}"#).unwrap();
+        let s = parse_json::<SimpleStruct>(value);
+        assert!(matches!(s, Err(GuruFocusError::SchemaMismatch { .. })));
+    }
+
+    #[test]
+    fn test_excerpt() {
+        assert_eq!(excerpt("  short  "), "short");
+        let long = "ä".repeat(BODY_EXCERPT_LEN + 10);
+        assert_eq!(excerpt(&long).chars().count(), BODY_EXCERPT_LEN + 3);
+    }
+
     #[tokio::test]
     async fn test_exchanges() {
         if let Ok(token) = env::var("GURUFOCUS_TOKEN") {
@@ -311,4 +1631,22 @@ mod tests {
             }
         }
     }
+
+    #[tokio::test]
+    async fn test_exchanges_typed() {
+        if let Ok(token) = env::var("GURUFOCUS_TOKEN") {
+            if !token.is_empty() {
+                let gf_connect = GuruFocusConnector::new(token);
+                let exchanges = gf_connect.exchanges().await;
+                assert!(exchanges.is_ok());
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn exchanges_from_fixture() {
+        let gf_connect = crate::replay::fixture_connector();
+        let exchanges = gf_connect.exchanges().await.unwrap();
+        assert_eq!(exchanges["Norway"], vec!["OSL"]);
+    }
 }
@@ -0,0 +1,256 @@
+//! Streams over paginated requests. Pages are requested lazily while the stream is
+//! consumed, and the stream ends after the last page, an empty page, the first error
+//! or after `PageOptions::max_pages` pages, whichever comes first.
+
+use futures::future::{self, Future};
+use futures::stream::{self, Stream, StreamExt, TryStreamExt};
+use std::ops::RangeInclusive;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use crate::gurus::{AssetType, GuruPick, PoliticianTransaction, PoliticianTransactionList};
+use crate::{GuruFocusConnector, GuruFocusError};
+
+/// Options for fetching paginated data
+#[derive(Debug, Clone, Copy)]
+pub struct PageOptions {
+    /// Number of pages requested concurrently. Since the total number of pages is not
+    /// always known in advance, up to `concurrency - 1` requests may be sent beyond the
+    /// last page.
+    pub concurrency: usize,
+    /// Maximum number of pages to be requested
+    pub max_pages: u32,
+}
+
+impl Default for PageOptions {
+    fn default() -> PageOptions {
+        PageOptions {
+            concurrency: 1,
+            max_pages: 100,
+        }
+    }
+}
+
+/// Request the given pages and pass them through until the first error or the first
+/// empty page. Pages not yet requested at that point are skipped.
+fn fetch_pages<'a, T, F, Fut>(
+    pages: RangeInclusive<u32>,
+    options: PageOptions,
+    fetch: F,
+    is_empty: fn(&T) -> bool,
+) -> impl Stream<Item = Result<T, GuruFocusError>> + 'a
+where
+    T: 'a,
+    F: Fn(u32) -> Fut + 'a,
+    Fut: Future<Output = Result<T, GuruFocusError>> + 'a,
+{
+    let stop = Arc::new(AtomicBool::new(false));
+    let stop_fetch = stop.clone();
+    stream::iter(pages)
+        .map(move |page| {
+            let request = (!stop_fetch.load(Ordering::Relaxed)).then(|| fetch(page));
+            async move {
+                match request {
+                    Some(request) => Some(request.await),
+                    None => None,
+                }
+            }
+        })
+        .buffered(options.concurrency.max(1))
+        .scan((), move |_, page| {
+            let page = match page {
+                Some(Ok(p)) if is_empty(&p) => None,
+                page => page,
+            };
+            if !matches!(page, Some(Ok(_))) {
+                stop.store(true, Ordering::Relaxed);
+            }
+            future::ready(page)
+        })
+}
+
+impl GuruFocusConnector {
+    /// Returns a stream of all picks of the given gurus since `start_date` as pairs
+    /// of guru id and pick.
+    pub fn guru_picks_stream<'a>(
+        &'a self,
+        gurus: &'a [&'a str],
+        start_date: chrono::NaiveDate,
+        options: PageOptions,
+    ) -> impl Stream<Item = Result<(String, GuruPick), GuruFocusError>> + 'a {
+        fetch_pages(
+            1..=options.max_pages,
+            options,
+            move |page| self.guru_picks(gurus, start_date, page as i32),
+            |page| page.values().all(|p| p.picks.is_empty()),
+        )
+        .map_ok(|page| {
+            stream::iter(page.into_iter().flat_map(|(guru, picks)| {
+                picks
+                    .picks
+                    .into_iter()
+                    .map(move |pick| Ok((guru.clone(), pick)))
+            }))
+        })
+        .try_flatten()
+    }
+
+    /// Returns a stream of all politician transactions, optionally restricted to
+    /// a single asset type.
+    pub fn politician_transactions_stream(
+        &self,
+        asset_type: Option<AssetType>,
+        options: PageOptions,
+    ) -> impl Stream<Item = Result<PoliticianTransaction, GuruFocusError>> + '_ {
+        let is_empty = |page: &PoliticianTransactionList| page.data.is_empty();
+        let fetch = move |page| self.politician_transactions(page, asset_type);
+        fetch_pages(1..=options.max_pages.min(1), options, fetch, is_empty)
+            .map(move |first| match first {
+                Ok(first) => {
+                    let last_page = first.last_page.min(options.max_pages);
+                    stream::once(future::ready(Ok(first)))
+                        .chain(fetch_pages(2..=last_page, options, fetch, is_empty))
+                        .left_stream()
+                }
+                Err(err) => stream::once(future::ready(Err(err))).right_stream(),
+            })
+            .flatten()
+            .map_ok(|page| stream::iter(page.data.into_iter().map(Ok)))
+            .try_flatten()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::TryStreamExt;
+    use serde_json::json;
+    use wiremock::matchers::{path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn transaction(id: u32) -> serde_json::Value {
+        json!({
+            "symbol": "AAPL", "company": "Apple Inc", "exchange": "NAS", "industry": 1,
+            "class": "Common Stock", "stockid": "US0D6D", "trans_type": "Purchase",
+            "amount": "$1,001 - $15,000", "disclosure_date": "2023-01-20",
+            "transaction_date": "2023-01-02", "id": id, "full_name": "Jane Doe",
+            "position": "Senator", "state": "NY", "party": "Independent"
+        })
+    }
+
+    fn transaction_page(page: u32, last_page: u32, ids: &[u32]) -> ResponseTemplate {
+        let data: Vec<_> = ids.iter().map(|id| transaction(*id)).collect();
+        ResponseTemplate::new(200).set_body_json(json!({
+            "count": data.len(), "currentPage": page, "lastPage": last_page,
+            "total": 5, "data": data
+        }))
+    }
+
+    async fn mock_transaction_pages(server: &MockServer) {
+        for (page, ids) in [(1, vec![1, 2]), (2, vec![3, 4]), (3, vec![5])] {
+            Mock::given(path("/tok/politicians/transactions"))
+                .and(query_param("page", page.to_string()))
+                .respond_with(transaction_page(page, 3, &ids))
+                .mount(server)
+                .await;
+        }
+    }
+
+    fn mock_connector(server: &MockServer) -> GuruFocusConnector {
+        GuruFocusConnector::builder("tok".to_string())
+            .base_url(&server.uri())
+            .build()
+    }
+
+    #[tokio::test]
+    async fn politician_transactions_all_pages() {
+        let server = MockServer::start().await;
+        mock_transaction_pages(&server).await;
+        let gf_connect = mock_connector(&server);
+        let options = PageOptions {
+            concurrency: 2,
+            ..Default::default()
+        };
+        let transactions: Vec<_> = gf_connect
+            .politician_transactions_stream(None, options)
+            .try_collect()
+            .await
+            .unwrap();
+        let ids: Vec<u32> = transactions.iter().map(|t| t.id).collect();
+        assert_eq!(ids, vec![1, 2, 3, 4, 5]);
+    }
+
+    #[tokio::test]
+    async fn politician_transactions_max_pages() {
+        let server = MockServer::start().await;
+        mock_transaction_pages(&server).await;
+        let gf_connect = mock_connector(&server);
+        let options = PageOptions {
+            concurrency: 1,
+            max_pages: 2,
+        };
+        let transactions: Vec<_> = gf_connect
+            .politician_transactions_stream(None, options)
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(transactions.len(), 4);
+    }
+
+    fn pick(company: &str) -> serde_json::Value {
+        json!({
+            "GuruName": "Warren Buffett", "RecmAction": "Add", "RecmDate": "2023-03-31",
+            "RecmPrice": 30.5, "change": 5.2, "comment": "Add 5.2%", "company": company,
+            "currency": "USD", "currency_txt": "$", "price": 31.0, "price_max": 33.1,
+            "price_min": 29.7, "sector": "Financial Services", "share_current": 1000000,
+            "symbol": "BAC", "symbol_ori": "BAC", "trans_share": 50000, "type": "Add",
+            "exchange": "NYSE", "industry": "Banks"
+        })
+    }
+
+    #[tokio::test]
+    async fn guru_picks_until_empty_page() {
+        let server = MockServer::start().await;
+        let pages = [
+            json!({"7": {"picks": [pick("A"), pick("B")]}}),
+            json!({"7": {"picks": [pick("C")]}}),
+            json!({"7": {"picks": []}}),
+        ];
+        for (page, body) in pages.iter().enumerate() {
+            Mock::given(path(format!("/tok/guru/7/picks/2023-01-01/{}", page + 1)))
+                .respond_with(ResponseTemplate::new(200).set_body_json(body))
+                .expect(1)
+                .mount(&server)
+                .await;
+        }
+        let gf_connect = mock_connector(&server);
+        let start = chrono::NaiveDate::from_ymd_opt(2023, 1, 1).unwrap();
+        let picks: Vec<_> = gf_connect
+            .guru_picks_stream(&["7"], start, PageOptions::default())
+            .try_collect()
+            .await
+            .unwrap();
+        let companies: Vec<&str> = picks.iter().map(|(_, p)| p.company.as_str()).collect();
+        assert_eq!(companies, vec!["A", "B", "C"]);
+        assert!(picks.iter().all(|(guru, _)| guru == "7"));
+    }
+
+    #[tokio::test]
+    async fn stream_ends_after_error() {
+        let server = MockServer::start().await;
+        Mock::given(path("/tok/guru/7/picks/2023-01-01/1"))
+            .respond_with(ResponseTemplate::new(500))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let gf_connect = mock_connector(&server);
+        let start = chrono::NaiveDate::from_ymd_opt(2023, 1, 1).unwrap();
+        let picks: Vec<_> = gf_connect
+            .guru_picks_stream(&["7"], start, PageOptions::default())
+            .collect()
+            .await;
+        assert_eq!(picks.len(), 1);
+        assert!(matches!(picks[0], Err(GuruFocusError::ServerError { .. })));
+        assert_eq!(server.received_requests().await.unwrap().len(), 1);
+    }
+}
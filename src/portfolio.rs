@@ -1,9 +1,11 @@
+use crate::extra::Extra;
 pub use crate::strnum::FloatOrString;
-use serde::Deserialize;
+use crate::symbol::Symbol;
+use chrono::{NaiveDate, NaiveDateTime};
+use serde::{Deserialize, Serialize};
 
 /// Container for a user portfolio
-#[derive(Deserialize, Debug)]
-#[serde(deny_unknown_fields)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct Portfolio {
     pub portid: String,
     pub portname: String,
@@ -14,11 +16,13 @@ pub struct Portfolio {
     pub introduction: String,
     pub private: FloatOrString,
     pub settings: String,
-    pub created: String,
+    #[serde(with = "crate::dates::date_time")]
+    pub created: NaiveDateTime,
     pub is_deleted: String,
     pub alert: String,
     pub email: String,
-    pub modified: String,
+    #[serde(with = "crate::dates::date_time")]
+    pub modified: NaiveDateTime,
     pub p_1m: FloatOrString,
     pub p_3m: FloatOrString,
     pub p_6m: FloatOrString,
@@ -29,7 +33,8 @@ pub struct Portfolio {
     pub p_all: FloatOrString,
     pub p_rel_sp500: FloatOrString,
     pub detail: Vec<Position>,
-    pub deleted_time: Option<String>,
+    #[serde(default, with = "crate::dates::option_date_time")]
+    pub deleted_time: Option<NaiveDateTime>,
     pub is_article: Option<String>,
     pub gain: Option<FloatOrString>,
     #[serde(rename = "type")]
@@ -38,20 +43,23 @@ pub struct Portfolio {
     pub view_id: String,
     pub stocks: FloatOrString,
     pub description: String,
+    /// Fields unknown to this crate, see `FieldMode`
+    #[serde(flatten)]
+    pub extra: Extra<Portfolio>,
 }
 
 /// Container for a stock position held by the user
-#[derive(Deserialize, Debug)]
-#[serde(deny_unknown_fields)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct Position {
     pub id: String,
     pub company: String,
     pub cost_per_share: FloatOrString,
     pub shares: FloatOrString,
-    pub symbol: String,
+    pub symbol: Symbol,
     pub volumn: FloatOrString,
     pub currency: String,
-    pub date_add: String,
+    #[serde(with = "crate::dates::date")]
+    pub date_add: NaiveDate,
     pub price: FloatOrString,
     pub pettm: FloatOrString,
     pub p_change: FloatOrString,
@@ -66,14 +74,27 @@ pub struct Position {
     pub in_price: FloatOrString,
     pub ps: FloatOrString,
     pub pb: FloatOrString,
+    /// Fields unknown to this crate, see `FieldMode`
+    #[serde(flatten)]
+    pub extra: Extra<Position>,
 }
 
 #[cfg(test)]
 mod test {
     use super::super::*;
-    use super::*;
+    use crate::replay::{assert_round_trip, fixture_connector};
     use std::env;
 
+    #[tokio::test]
+    async fn portfolio_from_fixture() {
+        let gf_connect = fixture_connector();
+        let portfolios = gf_connect.personal_portfolio().await.unwrap();
+        assert_eq!(portfolios[0].portname, "Dividend Growth");
+        assert_eq!(portfolios[0].detail[0].symbol, "MSFT");
+        assert!(portfolios[0].deleted_time.is_none());
+        assert_round_trip(&portfolios);
+    }
+
     #[tokio::test]
     async fn test_portfolio() {
         if let Ok(token) = env::var("GURUFOCUS_TOKEN") {
@@ -86,4 +107,15 @@ mod test {
             }
         }
     }
+
+    #[tokio::test]
+    async fn test_portfolio_typed() {
+        if let Ok(token) = env::var("GURUFOCUS_TOKEN") {
+            if !token.is_empty() {
+                let gf_connect = GuruFocusConnector::new(token);
+                let portfolios = gf_connect.personal_portfolio().await;
+                assert!(portfolios.is_ok());
+            }
+        }
+    }
 }
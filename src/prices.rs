@@ -0,0 +1,334 @@
+//! Price histories as returned by `price_hist` and `unadj_price_hist`, i.e. closing prices
+//! per trading day. `PriceSeries` keeps the prices ordered by date and provides lookups by
+//! date, resampling to weekly, monthly or yearly closes and the usual performance and risk
+//! figures (returns, volatility, drawdown, CAGR).
+
+use chrono::{Datelike, NaiveDate};
+use serde::de::Deserializer;
+use serde::ser::Serializer;
+use serde::{Deserialize, Serialize};
+use std::iter::FromIterator;
+use std::ops::{Bound, RangeBounds};
+
+/// Average number of days per year, used to annualize returns
+const DAYS_PER_YEAR: f64 = 365.25;
+
+/// Closing prices ordered by date, with at most one price per day
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct PriceSeries {
+    points: Vec<(NaiveDate, f64)>,
+}
+
+/// Period of resampled price series
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Frequency {
+    /// ISO calendar weeks
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+/// Largest loss from a peak to a subsequent trough
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Drawdown {
+    /// Date of the highest price before the trough
+    pub peak: NaiveDate,
+    /// Date of the lowest price after the peak
+    pub trough: NaiveDate,
+    /// Relative loss from peak to trough, e.g. `0.25` for a loss of 25%
+    pub depth: f64,
+}
+
+impl PriceSeries {
+    /// Create series from prices in any order; for duplicate dates, the last price is kept
+    pub fn new(mut points: Vec<(NaiveDate, f64)>) -> PriceSeries {
+        // stable sort keeps duplicates in their original order
+        points.sort_by_key(|(date, _)| *date);
+        let mut deduped: Vec<(NaiveDate, f64)> = Vec::with_capacity(points.len());
+        for point in points {
+            match deduped.last_mut() {
+                Some(last) if last.0 == point.0 => *last = point,
+                _ => deduped.push(point),
+            }
+        }
+        PriceSeries { points: deduped }
+    }
+
+    /// Returns all prices ordered by date
+    pub fn points(&self) -> &[(NaiveDate, f64)] {
+        &self.points
+    }
+
+    /// Returns an iterator over all prices ordered by date
+    pub fn iter(&self) -> impl Iterator<Item = &(NaiveDate, f64)> {
+        self.points.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    /// Returns the earliest price
+    pub fn first(&self) -> Option<(NaiveDate, f64)> {
+        self.points.first().copied()
+    }
+
+    /// Returns the latest price
+    pub fn last(&self) -> Option<(NaiveDate, f64)> {
+        self.points.last().copied()
+    }
+
+    /// Returns the price at the given date, if it is a trading day of the series
+    pub fn get(&self, date: NaiveDate) -> Option<f64> {
+        self.points
+            .binary_search_by_key(&date, |(d, _)| *d)
+            .ok()
+            .map(|idx| self.points[idx].1)
+    }
+
+    /// Returns the latest price at or before the given date, e.g. to get the price of a
+    /// weekend or holiday
+    pub fn at_or_before(&self, date: NaiveDate) -> Option<(NaiveDate, f64)> {
+        let idx = self.points.partition_point(|(d, _)| *d <= date);
+        idx.checked_sub(1).map(|idx| self.points[idx])
+    }
+
+    /// Returns the prices within the given range of dates, e.g. `range(start..=end)`
+    pub fn range<R: RangeBounds<NaiveDate>>(&self, range: R) -> PriceSeries {
+        let start = match range.start_bound() {
+            Bound::Included(date) => self.points.partition_point(|(d, _)| d < date),
+            Bound::Excluded(date) => self.points.partition_point(|(d, _)| d <= date),
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(date) => self.points.partition_point(|(d, _)| d <= date),
+            Bound::Excluded(date) => self.points.partition_point(|(d, _)| d < date),
+            Bound::Unbounded => self.points.len(),
+        };
+        PriceSeries {
+            points: self.points[start..end.max(start)].to_vec(),
+        }
+    }
+
+    /// Returns the last price of each week, month or year, dated by its trading day
+    pub fn resample(&self, frequency: Frequency) -> PriceSeries {
+        let period = |date: &NaiveDate| match frequency {
+            Frequency::Weekly => (date.iso_week().year(), date.iso_week().week()),
+            Frequency::Monthly => (date.year(), date.month()),
+            Frequency::Yearly => (date.year(), 1),
+        };
+        let mut points: Vec<(NaiveDate, f64)> = Vec::new();
+        for point in &self.points {
+            match points.last_mut() {
+                Some(last) if period(&last.0) == period(&point.0) => *last = *point,
+                _ => points.push(*point),
+            }
+        }
+        PriceSeries { points }
+    }
+
+    /// Returns the simple returns between consecutive prices, dated by the later price
+    pub fn returns(&self) -> Vec<(NaiveDate, f64)> {
+        self.points
+            .windows(2)
+            .map(|pair| (pair[1].0, pair[1].1 / pair[0].1 - 1.0))
+            .collect()
+    }
+
+    /// Returns the logarithmic returns between consecutive prices, dated by the later price
+    pub fn log_returns(&self) -> Vec<(NaiveDate, f64)> {
+        self.points
+            .windows(2)
+            .map(|pair| (pair[1].0, (pair[1].1 / pair[0].1).ln()))
+            .collect()
+    }
+
+    /// Returns the annualized volatility of the log returns over a rolling window of the
+    /// given number of returns, e.g. `rolling_volatility(20, 252.0)` for daily prices
+    pub fn rolling_volatility(
+        &self,
+        window: usize,
+        periods_per_year: f64,
+    ) -> Vec<(NaiveDate, f64)> {
+        if window < 2 {
+            return Vec::new();
+        }
+        self.log_returns()
+            .windows(window)
+            .map(|returns| {
+                let n = returns.len() as f64;
+                let mean = returns.iter().map(|(_, r)| r).sum::<f64>() / n;
+                let variance =
+                    returns.iter().map(|(_, r)| (r - mean).powi(2)).sum::<f64>() / (n - 1.0);
+                (returns[window - 1].0, (variance * periods_per_year).sqrt())
+            })
+            .collect()
+    }
+
+    /// Returns the largest relative loss from a peak to a subsequent trough, or `None`
+    /// if prices never fell below a previous peak
+    pub fn max_drawdown(&self) -> Option<Drawdown> {
+        let mut peak = *self.points.first()?;
+        let mut max: Option<Drawdown> = None;
+        for &(date, price) in &self.points[1..] {
+            if price > peak.1 {
+                peak = (date, price);
+                continue;
+            }
+            let depth = 1.0 - price / peak.1;
+            let deeper = match max {
+                Some(max) => depth > max.depth,
+                None => depth > 0.0,
+            };
+            if deeper {
+                max = Some(Drawdown {
+                    peak: peak.0,
+                    trough: date,
+                    depth,
+                });
+            }
+        }
+        max
+    }
+
+    /// Returns the compound annual growth rate from the first to the last price
+    pub fn cagr(&self) -> Option<f64> {
+        let (start_date, start) = self.first()?;
+        let (end_date, end) = self.last()?;
+        let years = (end_date - start_date).num_days() as f64 / DAYS_PER_YEAR;
+        if years > 0.0 && start > 0.0 && end > 0.0 {
+            Some((end / start).powf(1.0 / years) - 1.0)
+        } else {
+            None
+        }
+    }
+}
+
+impl From<Vec<(NaiveDate, f64)>> for PriceSeries {
+    fn from(points: Vec<(NaiveDate, f64)>) -> PriceSeries {
+        PriceSeries::new(points)
+    }
+}
+
+impl FromIterator<(NaiveDate, f64)> for PriceSeries {
+    fn from_iter<I: IntoIterator<Item = (NaiveDate, f64)>>(iter: I) -> PriceSeries {
+        PriceSeries::new(iter.into_iter().collect())
+    }
+}
+
+/// Single entry of the JSON representation `[date, price]`
+#[derive(Serialize, Deserialize)]
+struct PricePoint(#[serde(with = "crate::dates::date")] NaiveDate, f64);
+
+/// Read from the list of date and price returned by GuruFocus, e.g.
+/// `[["07-27-2020", 93.29], ...]`
+impl<'de> Deserialize<'de> for PriceSeries {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<PriceSeries, D::Error> {
+        let points = Vec::<PricePoint>::deserialize(deserializer)?;
+        Ok(points
+            .into_iter()
+            .map(|PricePoint(date, price)| (date, price))
+            .collect())
+    }
+}
+
+impl Serialize for PriceSeries {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(
+            self.points
+                .iter()
+                .map(|&(date, price)| PricePoint(date, price)),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::replay::{assert_round_trip, fixture_connector};
+    use crate::test_util::{assert_close, ymd};
+
+    #[tokio::test]
+    async fn price_series_from_fixture() {
+        let gf_connect = fixture_connector();
+        let prices = gf_connect.price_hist("NAS:AAPL").await.unwrap();
+        assert_eq!(prices.len(), 34);
+        assert_eq!(prices.first(), Some((ymd(2020, 7, 27), 93.2943)));
+        assert_eq!(prices.get(ymd(2020, 8, 31)), Some(129.04));
+        assert_eq!(prices.get(ymd(2020, 8, 30)), None);
+        assert_eq!(
+            prices.at_or_before(ymd(2020, 8, 30)),
+            Some((ymd(2020, 8, 28), 126.5225))
+        );
+        assert_eq!(prices.at_or_before(ymd(2020, 7, 1)), None);
+
+        let august = prices.range(ymd(2020, 8, 1)..ymd(2020, 9, 1));
+        assert_eq!(august.len(), 21);
+        assert_eq!(august.last(), Some((ymd(2020, 8, 31), 129.04)));
+        assert_eq!(prices.range(ymd(2020, 9, 10)..).len(), 2);
+
+        let monthly = prices.resample(Frequency::Monthly);
+        assert_eq!(
+            monthly.points(),
+            &[
+                (ymd(2020, 7, 31), 106.0688),
+                (ymd(2020, 8, 31), 129.04),
+                (ymd(2020, 9, 11), 112.0)
+            ]
+        );
+        let weekly = prices.resample(Frequency::Weekly);
+        assert_eq!(weekly.len(), 7);
+        assert_eq!(weekly.points()[5], (ymd(2020, 9, 4), 120.96));
+        assert_eq!(prices.resample(Frequency::Yearly).len(), 1);
+
+        let drawdown = prices.max_drawdown().unwrap();
+        assert_eq!(drawdown.peak, ymd(2020, 9, 1));
+        assert_eq!(drawdown.trough, ymd(2020, 9, 11));
+        assert_close(drawdown.depth, 1.0 - 112.0 / 134.18, 1e-12);
+        assert_round_trip(&prices);
+    }
+
+    #[test]
+    fn returns_and_volatility() {
+        let prices = PriceSeries::new(vec![
+            (ymd(2023, 1, 4), 110.0),
+            (ymd(2023, 1, 2), 100.0),
+            (ymd(2023, 1, 3), 90.0),
+            (ymd(2023, 1, 3), 105.0),
+            (ymd(2023, 1, 5), 99.0),
+        ]);
+        assert_eq!(prices.get(ymd(2023, 1, 3)), Some(105.0));
+        let returns = prices.returns();
+        assert_eq!(returns.len(), 3);
+        assert_close(returns[0].1, 0.05, 1e-12);
+        assert_close(returns[2].1, -0.1, 1e-12);
+        let log_returns = prices.log_returns();
+        let total: f64 = log_returns.iter().map(|(_, r)| r).sum();
+        assert_close(total, 0.99f64.ln(), 1e-12);
+
+        let volatility = prices.rolling_volatility(3, 252.0);
+        assert_eq!(volatility.len(), 1);
+        assert_eq!(volatility[0].0, ymd(2023, 1, 5));
+        let r: Vec<f64> = log_returns.iter().map(|(_, r)| *r).collect();
+        let mean = r.iter().sum::<f64>() / 3.0;
+        let variance = r.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / 2.0;
+        assert_close(volatility[0].1, (variance * 252.0).sqrt(), 1e-12);
+        assert!(prices.rolling_volatility(1, 252.0).is_empty());
+    }
+
+    #[test]
+    fn growth_rates() {
+        let prices: PriceSeries = vec![(ymd(2020, 1, 1), 100.0), (ymd(2022, 1, 1), 121.0)]
+            .into_iter()
+            .collect();
+        let cagr = prices.cagr().unwrap();
+        assert_close(cagr, 1.21f64.powf(DAYS_PER_YEAR / 731.0) - 1.0, 1e-12);
+        assert_close(cagr, 0.1, 1e-3);
+        assert_eq!(prices.max_drawdown(), None);
+        assert_eq!(PriceSeries::default().cagr(), None);
+    }
+}
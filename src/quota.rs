@@ -0,0 +1,157 @@
+//! Client-side throttling and accounting of requests sent to GuruFocus. The state is shared
+//! by all clones of a `GuruFocusConnector`, so that e.g. a batch job using several clones
+//! in parallel still respects the configured limits.
+
+use chrono::{NaiveDate, Utc};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::time::Instant;
+
+use crate::{Endpoint, GuruFocusError};
+
+/// Token bucket rate limiter allowing bursts of up to `capacity` requests
+#[derive(Debug)]
+pub(crate) struct RateLimiter {
+    capacity: f64,
+    /// Number of tokens added per second
+    refill_rate: f64,
+    state: Mutex<BucketState>,
+}
+
+#[derive(Debug)]
+struct BucketState {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl RateLimiter {
+    /// Allow at most `requests` requests within each time span of length `per`
+    pub(crate) fn new(requests: u32, per: Duration) -> RateLimiter {
+        let capacity = requests.max(1) as f64;
+        RateLimiter {
+            capacity,
+            refill_rate: capacity / per.as_secs_f64().max(f64::EPSILON),
+            state: Mutex::new(BucketState {
+                tokens: capacity,
+                last_refill: Instant::now(),
+            }),
+        }
+    }
+
+    /// Wait until a request may be sent
+    pub(crate) async fn acquire(&self) {
+        loop {
+            let wait = {
+                let mut state = self.state.lock().unwrap();
+                let now = Instant::now();
+                let elapsed = now.duration_since(state.last_refill).as_secs_f64();
+                state.tokens = (state.tokens + elapsed * self.refill_rate).min(self.capacity);
+                state.last_refill = now;
+                if state.tokens >= 1.0 {
+                    state.tokens -= 1.0;
+                    return;
+                }
+                Duration::from_secs_f64((1.0 - state.tokens) / self.refill_rate)
+            };
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+/// Snapshot of the number of requests sent to GuruFocus
+#[derive(Debug, Clone, Default)]
+pub struct RequestStats {
+    /// Number of requests per endpoint since the connector has been created
+    pub per_endpoint: HashMap<Endpoint, u64>,
+    /// Total number of requests since the connector has been created
+    pub total: u64,
+    /// Number of requests sent today (UTC)
+    pub today: u64,
+}
+
+/// Counter of requests with an optional daily budget
+#[derive(Debug)]
+pub(crate) struct QuotaTracker {
+    daily_budget: Option<u64>,
+    state: Mutex<QuotaState>,
+}
+
+#[derive(Debug, Default)]
+struct QuotaState {
+    stats: RequestStats,
+    day: Option<NaiveDate>,
+}
+
+impl QuotaTracker {
+    pub(crate) fn new(daily_budget: Option<u64>) -> QuotaTracker {
+        QuotaTracker {
+            daily_budget,
+            state: Mutex::new(QuotaState::default()),
+        }
+    }
+
+    /// Count a request to be sent, unless this would exceed the daily budget
+    pub(crate) fn register(&self, endpoint: Endpoint) -> Result<(), GuruFocusError> {
+        self.register_on(endpoint, Utc::now().date_naive())
+    }
+
+    fn register_on(&self, endpoint: Endpoint, today: NaiveDate) -> Result<(), GuruFocusError> {
+        let mut state = self.state.lock().unwrap();
+        if state.day != Some(today) {
+            state.day = Some(today);
+            state.stats.today = 0;
+        }
+        if let Some(budget) = self.daily_budget {
+            if state.stats.today >= budget {
+                return Err(GuruFocusError::BudgetExhausted { endpoint, budget });
+            }
+        }
+        state.stats.today += 1;
+        state.stats.total += 1;
+        *state.stats.per_endpoint.entry(endpoint).or_insert(0) += 1;
+        Ok(())
+    }
+
+    pub(crate) fn stats(&self) -> RequestStats {
+        self.state.lock().unwrap().stats.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(start_paused = true)]
+    async fn rate_limiter_delays_requests() {
+        let limiter = RateLimiter::new(2, Duration::from_secs(1));
+        let start = Instant::now();
+        for _ in 0..5 {
+            limiter.acquire().await;
+        }
+        // two requests as initial burst, the remaining three every 0.5s
+        let elapsed = start.elapsed();
+        assert!(elapsed >= Duration::from_millis(1500));
+        assert!(elapsed < Duration::from_millis(1600));
+    }
+
+    #[test]
+    fn daily_budget_resets_at_day_change() {
+        let tracker = QuotaTracker::new(Some(2));
+        let day1 = NaiveDate::from_ymd_opt(2023, 5, 1).unwrap();
+        let day2 = day1.succ_opt().unwrap();
+        assert!(tracker.register_on(Endpoint::Quote, day1).is_ok());
+        assert!(tracker.register_on(Endpoint::Summary, day1).is_ok());
+        assert!(matches!(
+            tracker.register_on(Endpoint::Quote, day1),
+            Err(GuruFocusError::BudgetExhausted { budget: 2, .. })
+        ));
+        assert!(tracker.register_on(Endpoint::Quote, day2).is_ok());
+
+        let stats = tracker.stats();
+        assert_eq!(stats.total, 3);
+        assert_eq!(stats.today, 1);
+        assert_eq!(stats.per_endpoint[&Endpoint::Quote], 2);
+        assert_eq!(stats.per_endpoint[&Endpoint::Summary], 1);
+    }
+}
@@ -0,0 +1,124 @@
+//! Recording of responses to fixture files and replay of these files instead of sending
+//! requests, e.g. to run tests deterministically without network access or API token.
+//! Fixture files are named after the request path (e.g. `stock_NAS%3AAAPL_quote.json` for
+//! request `stock/NAS:AAPL/quote`) and stored in a single directory.
+
+use serde_json::Value;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::cache::file_name;
+use crate::GuruFocusError;
+
+/// Placeholder replacing the user token in recorded fixtures
+pub const REDACTED_TOKEN: &str = "<TOKEN>";
+
+/// Record responses to or replay responses from a fixture directory
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FixtureMode {
+    /// Send requests and store each successful response as fixture file
+    Record(PathBuf),
+    /// Never send requests, but return the content of the fixture files
+    Replay(PathBuf),
+}
+
+impl FixtureMode {
+    fn dir(&self) -> &Path {
+        match self {
+            FixtureMode::Record(dir) | FixtureMode::Replay(dir) => dir,
+        }
+    }
+
+    /// Path of the fixture file for the given request path
+    pub fn fixture_file(&self, path: &str) -> PathBuf {
+        self.dir().join(file_name(path))
+    }
+
+    /// Load fixture of the given request path
+    pub(crate) fn load(&self, path: &str) -> Result<Value, GuruFocusError> {
+        let file = self.fixture_file(path);
+        let content = fs::read_to_string(&file).map_err(|_| GuruFocusError::FixtureMissing {
+            endpoint: path.to_string(),
+            file: file.clone(),
+        })?;
+        serde_json::from_str(&content).map_err(|source| GuruFocusError::InvalidJson {
+            endpoint: path.to_string(),
+            body: file.display().to_string(),
+            source,
+        })
+    }
+
+    /// Store response of the given request path with all occurrences of the token redacted
+    pub(crate) fn store(&self, path: &str, value: &Value, token: &str) -> io::Result<()> {
+        fs::create_dir_all(self.dir())?;
+        let mut content = serde_json::to_string_pretty(value)?;
+        if !token.is_empty() {
+            content = content.replace(token, REDACTED_TOKEN);
+        }
+        fs::write(self.fixture_file(path), content)
+    }
+}
+
+/// Connector replaying the fixtures shipped with this crate
+#[cfg(test)]
+pub(crate) fn fixture_connector() -> crate::GuruFocusConnector {
+    crate::GuruFocusConnector::builder(String::new())
+        .fixtures(FixtureMode::Replay(
+            Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures"),
+        ))
+        .build()
+}
+
+/// Check that serializing a value and parsing it again in strict mode yields the same value
+#[cfg(test)]
+pub(crate) fn assert_round_trip<T>(value: &T)
+where
+    T: serde::Serialize + serde::de::DeserializeOwned + PartialEq + std::fmt::Debug,
+{
+    let json = serde_json::to_value(value).unwrap();
+    let (copy, _) = crate::parse_json_with::<T>(json, crate::FieldMode::Strict).unwrap();
+    assert_eq!(&copy, value);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GuruFocusConnector;
+    use wiremock::matchers::path;
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[tokio::test]
+    async fn record_and_replay() {
+        let server = MockServer::start().await;
+        Mock::given(path("/secret/exchange_list"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_string(r#"{"USA": ["NAS"], "link": "https://x.com/secret/y"}"#),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+        let dir = tempfile::tempdir().unwrap();
+        let recorder = GuruFocusConnector::builder("secret".to_string())
+            .base_url(&server.uri())
+            .fixtures(FixtureMode::Record(dir.path().to_path_buf()))
+            .build();
+        recorder.get_exchanges().await.unwrap();
+
+        let content = fs::read_to_string(dir.path().join(file_name("exchange_list"))).unwrap();
+        assert!(!content.contains("secret"));
+        assert!(content.contains(REDACTED_TOKEN));
+
+        let replayer = GuruFocusConnector::builder("secret".to_string())
+            .base_url(&server.uri())
+            .fixtures(FixtureMode::Replay(dir.path().to_path_buf()))
+            .build();
+        let exchanges = replayer.get_exchanges().await.unwrap();
+        assert_eq!(exchanges["USA"][0], "NAS");
+        assert_eq!(exchanges["link"], "https://x.com/<TOKEN>/y");
+        let err = replayer.get_gurus().await.unwrap_err();
+        assert!(matches!(err, GuruFocusError::FixtureMissing { .. }));
+        assert_eq!(replayer.request_stats().total, 0);
+    }
+}
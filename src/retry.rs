@@ -0,0 +1,194 @@
+//! Retry of requests which failed due to transient errors, like a temporarily
+//! unavailable server or a connection reset.
+
+use chrono::{DateTime, Utc};
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::GuruFocusError;
+
+/// Policy for retrying failed requests with exponentially growing delays
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// Maximum number of attempts including the first one; 1 disables retries
+    pub max_attempts: u32,
+    /// Delay before the first retry
+    pub initial_delay: Duration,
+    /// Upper limit of the delay before a retry; if the server asks for a longer delay via
+    /// `Retry-After` header, the request is not retried
+    pub max_delay: Duration,
+    /// Factor by which the delay grows with each retry
+    pub multiplier: f64,
+    /// If true, the delay is randomly reduced by up to 50% to spread out retries of
+    /// concurrent requests
+    pub jitter: bool,
+    /// If true, a delay requested by the server via `Retry-After` header is respected
+    pub respect_retry_after: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: 3,
+            initial_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(30),
+            multiplier: 2.0,
+            jitter: true,
+            respect_retry_after: true,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Policy sending each request only once
+    pub fn no_retry() -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: 1,
+            ..Default::default()
+        }
+    }
+
+    /// Delay before the retry following the given (failed) attempt, starting at 1
+    pub(crate) fn delay(&self, attempt: u32, retry_after: Option<Duration>) -> Duration {
+        let exponent = attempt.saturating_sub(1).min(i32::MAX as u32) as i32;
+        let backoff = self.initial_delay.as_secs_f64() * self.multiplier.powi(exponent);
+        let mut delay = backoff.min(self.max_delay.as_secs_f64());
+        if self.jitter {
+            delay *= 0.5 + 0.5 * random_fraction();
+        }
+        let delay = Duration::from_secs_f64(delay);
+        match retry_after {
+            Some(retry_after) if self.respect_retry_after => {
+                delay.max(retry_after).min(self.max_delay)
+            }
+            _ => delay,
+        }
+    }
+
+    /// Returns the delay requested by the server if it is respected, but exceeds `max_delay`
+    pub(crate) fn excessive_retry_after(&self, retry_after: Option<Duration>) -> Option<Duration> {
+        retry_after.filter(|&retry_after| self.respect_retry_after && retry_after > self.max_delay)
+    }
+}
+
+/// Returns true if the request might succeed if sent again. Of the transport errors only
+/// timeouts, failed connections and broken response bodies are retried. Exhausted quota is
+/// only considered transient if the server tells when to retry.
+pub(crate) fn is_transient(error: &GuruFocusError, retry_after: Option<Duration>) -> bool {
+    match error {
+        GuruFocusError::RequestFailure(err) => {
+            err.is_timeout() || err.is_connect() || err.is_body()
+        }
+        GuruFocusError::ServerError { status, .. } => matches!(status, 500 | 502 | 503 | 504),
+        GuruFocusError::QuotaExceeded { .. } => retry_after.is_some(),
+        _ => false,
+    }
+}
+
+/// Parse the `Retry-After` header, given either as seconds or as HTTP date
+pub(crate) fn parse_retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let value = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let date = DateTime::parse_from_rfc2822(value).ok()?;
+    (date.with_timezone(&Utc) - Utc::now()).to_std().ok()
+}
+
+/// Pseudo random number in [0, 1), good enough to spread out retries
+fn random_fraction() -> f64 {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.subsec_nanos() as u64)
+        .unwrap_or_default();
+    // xorshift scrambling of time and call counter
+    let mut x = nanos ^ COUNTER.fetch_add(0x9E37_79B9_7F4A_7C15, Ordering::Relaxed);
+    x ^= x << 13;
+    x ^= x >> 7;
+    x ^= x << 17;
+    (x >> 11) as f64 / (1u64 << 53) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    #[test]
+    fn exponential_backoff() {
+        let policy = RetryPolicy {
+            max_attempts: 5,
+            initial_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(5),
+            multiplier: 2.0,
+            jitter: false,
+            respect_retry_after: true,
+        };
+        assert_eq!(policy.delay(1, None), Duration::from_secs(1));
+        assert_eq!(policy.delay(2, None), Duration::from_secs(2));
+        assert_eq!(policy.delay(3, None), Duration::from_secs(4));
+        assert_eq!(policy.delay(4, None), Duration::from_secs(5));
+        assert_eq!(
+            policy.delay(1, Some(Duration::from_secs(3))),
+            Duration::from_secs(3)
+        );
+        assert_eq!(
+            policy.delay(1, Some(Duration::from_secs(10))),
+            Duration::from_secs(5)
+        );
+        assert_eq!(policy.excessive_retry_after(None), None);
+        assert_eq!(
+            policy.excessive_retry_after(Some(Duration::from_secs(5))),
+            None
+        );
+        assert_eq!(
+            policy.excessive_retry_after(Some(Duration::from_secs(10))),
+            Some(Duration::from_secs(10))
+        );
+    }
+
+    #[test]
+    fn jitter_reduces_delay_by_at_most_half() {
+        let policy = RetryPolicy {
+            initial_delay: Duration::from_secs(2),
+            ..Default::default()
+        };
+        for _ in 0..100 {
+            let delay = policy.delay(1, None);
+            assert!(delay >= Duration::from_secs(1) && delay <= Duration::from_secs(2));
+        }
+    }
+
+    #[test]
+    fn retry_after_header() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(parse_retry_after(&headers), None);
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("120"));
+        assert_eq!(parse_retry_after(&headers), Some(Duration::from_secs(120)));
+        headers.insert(
+            RETRY_AFTER,
+            HeaderValue::from_static("Wed, 21 Oct 2015 07:28:00 GMT"),
+        );
+        // date in the past
+        assert_eq!(parse_retry_after(&headers), None);
+    }
+
+    #[test]
+    fn transient_errors() {
+        let server_error = |status| GuruFocusError::ServerError {
+            endpoint: String::new(),
+            status,
+            body: String::new(),
+        };
+        assert!(is_transient(&server_error(503), None));
+        assert!(!is_transient(&server_error(501), None));
+        let quota = GuruFocusError::QuotaExceeded {
+            endpoint: String::new(),
+            message: String::new(),
+        };
+        assert!(!is_transient(&quota, None));
+        assert!(is_transient(&quota, Some(Duration::from_secs(1))));
+    }
+}
@@ -0,0 +1,208 @@
+//! Reverse DCF: instead of valuing a stock for an assumed growth rate, solve for the growth
+//! rate of free cash flow during the growth stage which makes the DCF value (see
+//! `ValuationAssumptions::discounted_value`) equal to the current price.
+
+use crate::financials::FinancialData;
+use crate::stock::Quote;
+use crate::symbol::Symbol;
+use crate::valuation::ValuationAssumptions;
+use crate::{GuruFocusConnector, GuruFocusError};
+
+/// Lowest growth rate considered by the solver
+const MIN_IMPLIED_GROWTH: f64 = -0.99;
+/// Highest growth rate considered by the solver
+const MAX_IMPLIED_GROWTH: f64 = 100.0;
+/// Maximum deviation of the solution from the implied growth rate
+const GROWTH_TOLERANCE: f64 = 1e-10;
+
+/// Current price and free cash flow per share of a stock
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ReverseDcf {
+    pub price: f64,
+    /// Free cash flow per share of the trailing twelve months, or else of the latest
+    /// fiscal year
+    pub fcf_per_share: f64,
+}
+
+impl ReverseDcf {
+    /// Create from price and free cash flow per share in the same currency
+    pub fn new(price: f64, fcf_per_share: f64) -> ReverseDcf {
+        ReverseDcf {
+            price,
+            fcf_per_share,
+        }
+    }
+
+    /// Take the price from the quote and the free cash flow per share from the financial
+    /// data, which are assumed to be given in the same currency; returns `None` if either
+    /// is not available
+    pub fn from_data(quote: &Quote, financials: &FinancialData) -> Option<ReverseDcf> {
+        let price = quote.price.as_f64()?;
+        let fcf = financials
+            .financials
+            .annual_series("per_share_data_array", "Free Cash Flow per Share")
+            .ok()?;
+        let fcf_per_share = fcf.ttm().or_else(|| fcf.latest().map(|(_, fcf)| fcf))?;
+        Some(ReverseDcf::new(price, fcf_per_share))
+    }
+
+    /// Returns the growth rate of free cash flow during the growth stage implied by the
+    /// price, using discount rate, terminal growth and the number of years of both stages
+    /// of the given assumptions (their growth rates and bounds are ignored). Returns `None`
+    /// for non-positive price or free cash flow, or if the price can not be reached within
+    /// growth rates of -99% and 10000%.
+    pub fn implied_growth(&self, assumptions: &ValuationAssumptions) -> Option<f64> {
+        if self.price <= 0.0 || self.fcf_per_share <= 0.0 {
+            return None;
+        }
+        // the DCF value is strictly increasing in the growth rate
+        let value = |growth| assumptions.discounted_value(self.fcf_per_share, growth);
+        let (mut low, mut high) = (MIN_IMPLIED_GROWTH, 1.0);
+        if value(low) > self.price {
+            return None;
+        }
+        while value(high) < self.price {
+            if high >= MAX_IMPLIED_GROWTH {
+                return None;
+            }
+            low = high;
+            high = (high * 2.0).min(MAX_IMPLIED_GROWTH);
+        }
+        while high - low > GROWTH_TOLERANCE {
+            let mid = (low + high) / 2.0;
+            if value(mid) < self.price {
+                low = mid;
+            } else {
+                high = mid;
+            }
+        }
+        Some((low + high) / 2.0)
+    }
+
+    /// Returns the implied growth rates for all combinations of the given discount rates
+    /// and terminal growth rates, all other assumptions taken from `assumptions`
+    pub fn sensitivity(
+        &self,
+        assumptions: &ValuationAssumptions,
+        discount_rates: &[f64],
+        terminal_growths: &[f64],
+    ) -> SensitivityTable {
+        let implied_growth = discount_rates
+            .iter()
+            .map(|&discount_rate| {
+                terminal_growths
+                    .iter()
+                    .map(|&terminal_growth| {
+                        self.implied_growth(&ValuationAssumptions {
+                            discount_rate,
+                            terminal_growth,
+                            ..*assumptions
+                        })
+                    })
+                    .collect()
+            })
+            .collect();
+        SensitivityTable {
+            discount_rates: discount_rates.to_vec(),
+            terminal_growths: terminal_growths.to_vec(),
+            implied_growth,
+        }
+    }
+}
+
+/// Implied growth rates with one row per discount rate and one column per terminal
+/// growth rate
+#[derive(Debug, Clone, PartialEq)]
+pub struct SensitivityTable {
+    pub discount_rates: Vec<f64>,
+    pub terminal_growths: Vec<f64>,
+    pub implied_growth: Vec<Vec<Option<f64>>>,
+}
+
+impl SensitivityTable {
+    /// Returns the implied growth rate for the discount rate in row `row` and the terminal
+    /// growth rate in column `column`
+    pub fn get(&self, row: usize, column: usize) -> Option<f64> {
+        *self.implied_growth.get(row)?.get(column)?
+    }
+}
+
+impl GuruFocusConnector {
+    /// Returns price and free cash flow per share of the symbol given as argument for the
+    /// reverse DCF, requesting its quote and financials; `None` if either is not available
+    pub async fn reverse_dcf(
+        &self,
+        stock: impl Into<Symbol>,
+    ) -> Result<Option<ReverseDcf>, GuruFocusError> {
+        let symbol = stock.into();
+        let (quotes, financials) = futures::future::try_join(
+            self.quotes(std::slice::from_ref(&symbol)),
+            self.financials(&symbol),
+        )
+        .await?;
+        Ok(quotes
+            .first()
+            .and_then(|quote| ReverseDcf::from_data(quote, &financials)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::replay::fixture_connector;
+    use crate::test_util::assert_close;
+
+    #[tokio::test]
+    async fn implied_growth_from_fixture() {
+        let gf_connect = fixture_connector();
+        let quotes = gf_connect.quotes(&["NAS:MSFT", "NAS:AAPL"]).await.unwrap();
+        let financials = gf_connect.financials("NAS:MSFT").await.unwrap();
+        let reverse_dcf = ReverseDcf::from_data(&quotes[0], &financials).unwrap();
+        assert_eq!(reverse_dcf, ReverseDcf::new(376.04, 8.31));
+
+        let assumptions = ValuationAssumptions::default();
+        let growth = reverse_dcf.implied_growth(&assumptions).unwrap();
+        let value = assumptions.discounted_value(8.31, growth);
+        assert_close(value, 376.04, 1e-6);
+        assert!(growth > 0.2 && growth < 0.3);
+
+        let table = reverse_dcf.sensitivity(&assumptions, &[0.08, 0.1, 0.12], &[0.02, 0.04]);
+        assert_eq!(table.implied_growth.len(), 3);
+        assert_eq!(
+            table.get(1, 1),
+            reverse_dcf.implied_growth(&ValuationAssumptions {
+                discount_rate: 0.1,
+                ..assumptions
+            })
+        );
+        // higher discount rates require higher growth, higher terminal growth less growth
+        assert!(table.get(0, 0).unwrap() < table.get(2, 0).unwrap());
+        assert!(table.get(0, 1).unwrap() < table.get(0, 0).unwrap());
+        assert_eq!(table.get(3, 0), None);
+    }
+
+    #[test]
+    fn bounds_of_implied_growth() {
+        let assumptions = ValuationAssumptions::default();
+        assert_eq!(
+            ReverseDcf::new(100.0, -1.0).implied_growth(&assumptions),
+            None
+        );
+        assert_eq!(ReverseDcf::new(0.0, 1.0).implied_growth(&assumptions), None);
+        // zero growth matches the value without growth
+        let price = assumptions.discounted_value(2.0, 0.0);
+        let growth = ReverseDcf::new(price, 2.0)
+            .implied_growth(&assumptions)
+            .unwrap();
+        assert_close(growth, 0.0, 1e-8);
+        // prices beyond reach of the solver
+        assert_eq!(
+            ReverseDcf::new(1e300, 1.0).implied_growth(&assumptions),
+            None
+        );
+        assert_eq!(
+            ReverseDcf::new(1e-9, 1.0).implied_growth(&assumptions),
+            None
+        );
+    }
+}
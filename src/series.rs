@@ -0,0 +1,310 @@
+//! Time series view of `FinancialData`. Each metric of `PeriodData` is an array with one
+//! entry per column of `fiscal_year`, and the `preliminary` flags apply to these columns as
+//! well. `FinancialSeries` combines them into entries of fiscal period, value (if given as
+//! a number) and preliminary flag, and offers derived series like the growth rate versus
+//! the previous year or the sum of the trailing twelve months of quarterly data.
+
+use chrono::{Datelike, NaiveDate};
+
+use crate::dates::{month_end, FiscalPeriod};
+use crate::financials::{DataPeriods, FloatOrString, PeriodData};
+use crate::GuruFocusError;
+
+/// Single entry of a financial series: fiscal period, value and preliminary flag
+pub type SeriesEntry = (FiscalPeriod, Option<f64>, bool);
+
+/// Values of a single metric per fiscal period, ordered as delivered by GuruFocus, i.e.
+/// oldest period first and the trailing twelve months (if any) last
+#[derive(Debug, Clone, PartialEq)]
+pub struct FinancialSeries {
+    entries: Vec<SeriesEntry>,
+}
+
+impl FinancialSeries {
+    /// Create series from entries of fiscal period, value and preliminary flag
+    pub fn new(entries: Vec<SeriesEntry>) -> FinancialSeries {
+        FinancialSeries { entries }
+    }
+
+    /// Returns all entries including the trailing twelve months
+    pub fn entries(&self) -> &[SeriesEntry] {
+        &self.entries
+    }
+
+    /// Returns the value of the given period, if available
+    pub fn get(&self, period: FiscalPeriod) -> Option<f64> {
+        self.entries
+            .iter()
+            .find(|(p, _, _)| *p == period)
+            .and_then(|(_, value, _)| *value)
+    }
+
+    /// Returns the value of the trailing twelve months, if available
+    pub fn ttm(&self) -> Option<f64> {
+        self.get(FiscalPeriod::Ttm)
+    }
+
+    /// Returns the series without the column of the trailing twelve months
+    pub fn without_ttm(&self) -> FinancialSeries {
+        self.filter(|(period, _, _)| !period.is_ttm())
+    }
+
+    /// Returns the latest fiscal period with a value, ignoring the trailing twelve months
+    pub fn latest(&self) -> Option<(FiscalPeriod, f64)> {
+        self.entries
+            .iter()
+            .rev()
+            .filter(|(period, _, _)| !period.is_ttm())
+            .find_map(|(period, value, _)| value.map(|value| (*period, value)))
+    }
+
+    /// Returns the entries of both series for all periods contained in both, e.g. to
+    /// compare annual data with the quarterly data of the last quarter of each year
+    pub fn align(&self, other: &FinancialSeries) -> Vec<(FiscalPeriod, Option<f64>, Option<f64>)> {
+        self.entries
+            .iter()
+            .filter_map(|(period, value, _)| {
+                other
+                    .entries
+                    .iter()
+                    .find(|(p, _, _)| p == period)
+                    .map(|(_, other_value, _)| (*period, *value, *other_value))
+            })
+            .collect()
+    }
+
+    /// Returns the growth rate of each period versus the period ending one year earlier
+    /// (e.g. 0.05 for 5%), for annual as well as for quarterly data. Growth rates are only
+    /// given for positive values of the previous year.
+    pub fn yoy_growth(&self) -> FinancialSeries {
+        let entries = self
+            .entries
+            .iter()
+            .filter_map(|(period, value, preliminary)| {
+                let date = period.end_date()?;
+                let previous = self.get(FiscalPeriod::Period(year_before(date, 1)?));
+                let growth = match (value, previous) {
+                    (Some(value), Some(previous)) if previous > 0.0 => Some(value / previous - 1.0),
+                    _ => None,
+                };
+                Some((*period, growth, *preliminary))
+            })
+            .collect();
+        FinancialSeries { entries }
+    }
+
+    /// Returns the compound annual growth rate over the given number of years, ending with
+    /// the latest period with a value; requires positive values at start and end
+    pub fn cagr(&self, years: u32) -> Option<f64> {
+        if years == 0 {
+            return None;
+        }
+        let (period, end) = self.latest()?;
+        let start_date = year_before(period.end_date()?, years)?;
+        let start = self.get(FiscalPeriod::Period(start_date))?;
+        if start > 0.0 && end > 0.0 {
+            Some((end / start).powf(1.0 / years as f64) - 1.0)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the sums of four consecutive quarters of quarterly data, labeled by the last
+    /// of these quarters; sums including a preliminary quarter are marked as preliminary
+    pub fn rolling_ttm(&self) -> FinancialSeries {
+        let quarters = self.without_ttm().entries;
+        let entries = quarters
+            .windows(4)
+            .filter_map(|window| {
+                let dates: Option<Vec<NaiveDate>> = window
+                    .iter()
+                    .map(|(period, _, _)| period.end_date())
+                    .collect();
+                let dates = dates?;
+                // skip windows with gaps, e.g. due to missing quarters
+                let consecutive = dates
+                    .windows(2)
+                    .all(|pair| months_between(pair[0], pair[1]) == 3);
+                if !consecutive {
+                    return None;
+                }
+                let sum = window.iter().map(|(_, value, _)| *value).sum();
+                let preliminary = window.iter().any(|(_, _, preliminary)| *preliminary);
+                Some((window[3].0, sum, preliminary))
+            })
+            .collect();
+        FinancialSeries { entries }
+    }
+
+    fn filter<F: Fn(&SeriesEntry) -> bool>(&self, predicate: F) -> FinancialSeries {
+        FinancialSeries {
+            entries: self.entries.iter().copied().filter(predicate).collect(),
+        }
+    }
+}
+
+/// End of the same month the given number of years earlier
+fn year_before(date: NaiveDate, years: u32) -> Option<NaiveDate> {
+    month_end(date.year() - years as i32, date.month())
+}
+
+fn months_between(from: NaiveDate, to: NaiveDate) -> i32 {
+    (to.year() - from.year()) * 12 + to.month() as i32 - from.month() as i32
+}
+
+impl PeriodData {
+    /// Returns the series of the metric `name` in the given section, e.g.
+    /// `series("income_statement", "Revenue")`
+    pub fn series(&self, section: &str, name: &str) -> Result<FinancialSeries, GuruFocusError> {
+        let values = match section {
+            "per_share_data_array" => &self.per_share_data_array,
+            "common_size_ratios" => &self.common_size_ratios,
+            "income_statement" => &self.income_statement,
+            "balance_sheet" => &self.balance_sheet,
+            "cashflow_statement" => &self.cashflow_statement,
+            "valuation_ratios" => &self.valuation_ratios,
+            "valuation_and_quality" => &self.valuation_and_quality,
+            _ => &serde_json::Value::Null,
+        }
+        .get(name)
+        .and_then(|values| values.as_array())
+        .ok_or_else(|| GuruFocusError::UnknownMetric {
+            section: section.to_string(),
+            name: name.to_string(),
+        })?;
+        let entries = self
+            .fiscal_year
+            .iter()
+            .zip(values)
+            .enumerate()
+            .map(|(idx, (period, value))| {
+                let value = serde_json::from_value::<FloatOrString>(value.clone())
+                    .ok()
+                    .and_then(|value| value.as_f64());
+                let preliminary = self
+                    .preliminary
+                    .get(idx)
+                    .and_then(FloatOrString::as_f64)
+                    .is_some_and(|flag| flag != 0.0);
+                (*period, value, preliminary)
+            })
+            .collect();
+        Ok(FinancialSeries { entries })
+    }
+}
+
+impl DataPeriods {
+    /// Returns the annual series of the metric `name` in the given section
+    pub fn annual_series(
+        &self,
+        section: &str,
+        name: &str,
+    ) -> Result<FinancialSeries, GuruFocusError> {
+        self.annuals.series(section, name)
+    }
+
+    /// Returns the quarterly series of the metric `name` in the given section
+    pub fn quarterly_series(
+        &self,
+        section: &str,
+        name: &str,
+    ) -> Result<FinancialSeries, GuruFocusError> {
+        self.quarterly.series(section, name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::replay::fixture_connector;
+    use crate::test_util::assert_close;
+
+    fn month(year: i32, month: u32) -> FiscalPeriod {
+        FiscalPeriod::Period(month_end(year, month).unwrap())
+    }
+
+    #[tokio::test]
+    async fn series_from_fixture() {
+        let gf_connect = fixture_connector();
+        let financials = gf_connect.financials("NAS:MSFT").await.unwrap().financials;
+        let annual = financials
+            .annual_series("income_statement", "Revenue")
+            .unwrap();
+        assert_eq!(annual.entries().len(), 6);
+        assert_eq!(annual.get(month(2023, 6)), Some(211915.0));
+        assert_eq!(annual.ttm(), Some(227583.0));
+        assert_eq!(annual.without_ttm().entries().len(), 5);
+        assert_eq!(annual.latest(), Some((month(2023, 6), 211915.0)));
+
+        let growth = annual.yoy_growth();
+        assert_eq!(growth.entries().len(), 5);
+        assert_eq!(growth.get(month(2019, 6)), None);
+        assert_close(
+            growth.get(month(2023, 6)).unwrap(),
+            211915.0 / 198270.0 - 1.0,
+            1e-9,
+        );
+        assert_close(
+            annual.cagr(4).unwrap(),
+            (211915.0f64 / 125843.0).powf(0.25) - 1.0,
+            1e-9,
+        );
+        assert_eq!(annual.cagr(5), None);
+
+        let quarterly = financials
+            .quarterly_series("income_statement", "Revenue")
+            .unwrap();
+        assert!(quarterly.entries()[4].2);
+        let rolling = quarterly.rolling_ttm();
+        assert_eq!(
+            rolling.entries(),
+            &[
+                (month(2023, 9), Some(218310.0), false),
+                (month(2023, 12), Some(227583.0), true)
+            ]
+        );
+        assert_eq!(rolling.latest().map(|(_, sum)| sum), quarterly.ttm());
+
+        let aligned = annual.align(&quarterly);
+        assert_eq!(
+            aligned,
+            vec![
+                (month(2023, 6), Some(211915.0), Some(56189.0)),
+                (FiscalPeriod::Ttm, Some(227583.0), Some(227583.0))
+            ]
+        );
+
+        let err = financials
+            .annual_series("income_statement", "Revenues")
+            .unwrap_err();
+        assert!(matches!(err, GuruFocusError::UnknownMetric { .. }));
+    }
+
+    #[test]
+    fn gaps_and_missing_values() {
+        let series = FinancialSeries::new(vec![
+            (month(2022, 3), Some(1.0), false),
+            (month(2022, 6), Some(2.0), false),
+            (month(2022, 9), None, false),
+            (month(2022, 12), Some(4.0), false),
+            (month(2023, 3), Some(-1.0), false),
+            (month(2023, 9), Some(6.0), false),
+            (month(2023, 12), Some(7.0), false),
+            (month(2024, 3), Some(8.0), false),
+        ]);
+        let rolling = series.rolling_ttm();
+        assert_eq!(
+            rolling.entries(),
+            &[
+                (month(2022, 12), None, false),
+                (month(2023, 3), None, false)
+            ]
+        );
+        let growth = series.yoy_growth();
+        assert_eq!(growth.get(month(2023, 3)), Some(-2.0));
+        assert_eq!(growth.get(month(2023, 9)), None);
+        assert_eq!(growth.get(month(2023, 12)), Some(0.75));
+        assert_eq!(growth.get(month(2024, 3)), None);
+        assert_eq!(series.cagr(1), None);
+    }
+}
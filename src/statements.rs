@@ -0,0 +1,689 @@
+//! Typed view of the financial statements contained in `PeriodData`. GuruFocus uses
+//! different statement layouts for banks, insurance companies and all other companies,
+//! REITs report some additional figures and the cash flow statement is given according
+//! to either the direct or the indirect method. The layout is selected from the
+//! `FinancialTemplateParameters` delivered together with the data.
+//!
+//! The statements are decoded on demand from the raw JSON sections of `PeriodData`. Each
+//! field is a series with one entry per column of `PeriodData::fiscal_year`; line items
+//! not reported by GuruFocus result in an empty series. Line items without a field of
+//! their own are kept in the member `extra` of the statement.
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::dates::FiscalPeriod;
+use crate::extra::{parse_json_with, Extra, FieldMode};
+use crate::financials::{DataPeriods, FinancialTemplateParameters, PeriodData};
+use crate::strnum::FloatOrString;
+use crate::GuruFocusError;
+
+/// Values of a single line item, one per fiscal period
+pub type Series = Vec<FloatOrString>;
+
+/// Statement layout used by GuruFocus, as given by `ind_template`
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IndustryTemplate {
+    /// Template for all companies except banks and insurances (`N`)
+    NonFinancial,
+    /// Template for banks (`B`)
+    Bank,
+    /// Template for insurance companies (`I`)
+    Insurance,
+}
+
+impl FinancialTemplateParameters {
+    /// Returns the statement layout used for the company
+    pub fn template(&self) -> Result<IndustryTemplate, GuruFocusError> {
+        match self.ind_template.trim() {
+            "N" => Ok(IndustryTemplate::NonFinancial),
+            "B" => Ok(IndustryTemplate::Bank),
+            "I" => Ok(IndustryTemplate::Insurance),
+            other => Err(GuruFocusError::UnsupportedTemplate {
+                template: other.to_string(),
+            }),
+        }
+    }
+
+    /// Returns true if the company is a REIT
+    pub fn is_reit(&self) -> bool {
+        self.reits.trim() == "Y"
+    }
+
+    /// Returns true if the cash flow statement follows the direct method
+    pub fn is_direct(&self) -> bool {
+        self.is_direct.trim() == "Y"
+    }
+}
+
+impl PeriodData {
+    /// Decode the financial statements according to the given template parameters
+    pub fn statements(
+        &self,
+        parameters: &FinancialTemplateParameters,
+    ) -> Result<Statements, GuruFocusError> {
+        let template = parameters.template()?;
+        let income_statement = match template {
+            IndustryTemplate::NonFinancial => Income::NonFinancial(decode(&self.income_statement)?),
+            IndustryTemplate::Bank => Income::Bank(decode(&self.income_statement)?),
+            IndustryTemplate::Insurance => Income::Insurance(decode(&self.income_statement)?),
+        };
+        let balance_sheet = match template {
+            IndustryTemplate::NonFinancial => {
+                BalanceSheet::NonFinancial(decode(&self.balance_sheet)?)
+            }
+            IndustryTemplate::Bank => BalanceSheet::Bank(decode(&self.balance_sheet)?),
+            IndustryTemplate::Insurance => BalanceSheet::Insurance(decode(&self.balance_sheet)?),
+        };
+        let cashflow_statement = if parameters.is_direct() {
+            CashFlow::Direct(decode(&self.cashflow_statement)?)
+        } else {
+            CashFlow::Indirect(decode(&self.cashflow_statement)?)
+        };
+        let reit = if parameters.is_reit() {
+            Some(ReitData {
+                ffo: decode::<ReitIncome>(&self.income_statement)?.ffo,
+                ffo_per_share: decode::<ReitPerShare>(&self.per_share_data_array)?.ffo_per_share,
+            })
+        } else {
+            None
+        };
+        Ok(Statements {
+            template,
+            periods: self.fiscal_year.clone(),
+            per_share: decode(&self.per_share_data_array)?,
+            income_statement,
+            balance_sheet,
+            cashflow_statement,
+            reit,
+        })
+    }
+}
+
+impl DataPeriods {
+    /// Decode the annual financial statements
+    pub fn annual_statements(&self) -> Result<Statements, GuruFocusError> {
+        self.annuals.statements(&self.financial_template_parameters)
+    }
+
+    /// Decode the quarterly financial statements
+    pub fn quarterly_statements(&self) -> Result<Statements, GuruFocusError> {
+        self.quarterly
+            .statements(&self.financial_template_parameters)
+    }
+}
+
+/// Decode a section of `PeriodData`, keeping line items unknown to `T` in its `extra` member
+fn decode<T: DeserializeOwned>(section: &Value) -> Result<T, GuruFocusError> {
+    // sections not delivered at all are treated like sections without line items
+    let section = match section {
+        Value::Null => Value::Object(Default::default()),
+        section => section.clone(),
+    };
+    parse_json_with(section, FieldMode::Lenient).map(|(data, _)| data)
+}
+
+/// Financial statements of a company for a sequence of fiscal periods
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Statements {
+    /// Layout of the statements
+    pub template: IndustryTemplate,
+    /// Fiscal periods the entries of each series belong to
+    pub periods: Vec<FiscalPeriod>,
+    pub per_share: PerShareData,
+    pub income_statement: Income,
+    pub balance_sheet: BalanceSheet,
+    pub cashflow_statement: CashFlow,
+    /// Additional figures reported for REITs only
+    pub reit: Option<ReitData>,
+}
+
+/// Per share figures, reported in the same layout for all templates
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PerShareData {
+    #[serde(rename = "Revenue per Share", default)]
+    pub revenue: Series,
+    #[serde(rename = "EPS (Diluted)", default)]
+    pub eps_diluted: Series,
+    #[serde(rename = "EPS without NRI", default)]
+    pub eps_without_nri: Series,
+    #[serde(rename = "Free Cash Flow per Share", default)]
+    pub free_cash_flow: Series,
+    #[serde(rename = "Dividends per Share", default)]
+    pub dividends: Series,
+    #[serde(rename = "Book Value per Share", default)]
+    pub book_value: Series,
+    #[serde(rename = "Tangible Book per Share", default)]
+    pub tangible_book: Series,
+    #[serde(rename = "Total Debt per Share", default)]
+    pub total_debt: Series,
+    #[serde(rename = "Month End Stock Price", default)]
+    pub month_end_price: Series,
+    /// Line items without a field of their own
+    #[serde(flatten)]
+    pub extra: Extra<PerShareData>,
+}
+
+/// Income statement in the layout of the company's template
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum Income {
+    NonFinancial(NonFinancialIncome),
+    Bank(BankIncome),
+    Insurance(InsuranceIncome),
+}
+
+impl Income {
+    /// Total revenue
+    pub fn revenue(&self) -> &Series {
+        match self {
+            Income::NonFinancial(income) => &income.revenue,
+            Income::Bank(income) => &income.revenue,
+            Income::Insurance(income) => &income.revenue,
+        }
+    }
+
+    /// Income before taxes
+    pub fn pretax_income(&self) -> &Series {
+        match self {
+            Income::NonFinancial(income) => &income.pretax_income,
+            Income::Bank(income) => &income.pretax_income,
+            Income::Insurance(income) => &income.pretax_income,
+        }
+    }
+
+    /// Net income
+    pub fn net_income(&self) -> &Series {
+        match self {
+            Income::NonFinancial(income) => &income.net_income,
+            Income::Bank(income) => &income.net_income,
+            Income::Insurance(income) => &income.net_income,
+        }
+    }
+}
+
+/// Income statement of non-financial companies
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct NonFinancialIncome {
+    #[serde(rename = "Revenue", default)]
+    pub revenue: Series,
+    #[serde(rename = "Cost of Goods Sold", default)]
+    pub cost_of_goods_sold: Series,
+    #[serde(rename = "Gross Profit", default)]
+    pub gross_profit: Series,
+    #[serde(rename = "Operating Income", default)]
+    pub operating_income: Series,
+    #[serde(rename = "EBIT", default)]
+    pub ebit: Series,
+    #[serde(rename = "EBITDA", default)]
+    pub ebitda: Series,
+    #[serde(rename = "Interest Expense", default)]
+    pub interest_expense: Series,
+    #[serde(rename = "Pre-Tax Income", default)]
+    pub pretax_income: Series,
+    #[serde(rename = "Tax Rate %", default)]
+    pub tax_rate: Series,
+    #[serde(rename = "Net Income", default)]
+    pub net_income: Series,
+    #[serde(rename = "Shares Outstanding (Diluted Average)", default)]
+    pub shares_diluted: Series,
+    /// Line items without a field of their own
+    #[serde(flatten)]
+    pub extra: Extra<NonFinancialIncome>,
+}
+
+/// Income statement of banks
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct BankIncome {
+    #[serde(rename = "Revenue", default)]
+    pub revenue: Series,
+    #[serde(rename = "Interest Income", default)]
+    pub interest_income: Series,
+    #[serde(rename = "Interest Expense", default)]
+    pub interest_expense: Series,
+    #[serde(rename = "Net Interest Income", default)]
+    pub net_interest_income: Series,
+    #[serde(rename = "Non Interest Income", default)]
+    pub non_interest_income: Series,
+    #[serde(rename = "Credit Losses Provision", default)]
+    pub credit_losses_provision: Series,
+    #[serde(rename = "Non Interest Expense", default)]
+    pub non_interest_expense: Series,
+    #[serde(rename = "Pre-Tax Income", default)]
+    pub pretax_income: Series,
+    #[serde(rename = "Tax Rate %", default)]
+    pub tax_rate: Series,
+    #[serde(rename = "Net Income", default)]
+    pub net_income: Series,
+    #[serde(rename = "Shares Outstanding (Diluted Average)", default)]
+    pub shares_diluted: Series,
+    /// Line items without a field of their own
+    #[serde(flatten)]
+    pub extra: Extra<BankIncome>,
+}
+
+/// Income statement of insurance companies
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct InsuranceIncome {
+    #[serde(rename = "Revenue", default)]
+    pub revenue: Series,
+    #[serde(rename = "Net Premiums Earned", default)]
+    pub net_premiums_earned: Series,
+    #[serde(rename = "Net Investment Income", default)]
+    pub net_investment_income: Series,
+    #[serde(rename = "Fees and Other Income", default)]
+    pub fees_and_other_income: Series,
+    #[serde(rename = "Net Policyholder Benefits/Claims", default)]
+    pub policyholder_benefits: Series,
+    #[serde(rename = "Policy Acquisition Expense", default)]
+    pub policy_acquisition_expense: Series,
+    #[serde(rename = "Pre-Tax Income", default)]
+    pub pretax_income: Series,
+    #[serde(rename = "Tax Rate %", default)]
+    pub tax_rate: Series,
+    #[serde(rename = "Net Income", default)]
+    pub net_income: Series,
+    #[serde(rename = "Shares Outstanding (Diluted Average)", default)]
+    pub shares_diluted: Series,
+    /// Line items without a field of their own
+    #[serde(flatten)]
+    pub extra: Extra<InsuranceIncome>,
+}
+
+/// Balance sheet in the layout of the company's template
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum BalanceSheet {
+    NonFinancial(NonFinancialBalanceSheet),
+    Bank(BankBalanceSheet),
+    Insurance(InsuranceBalanceSheet),
+}
+
+impl BalanceSheet {
+    /// Total assets
+    pub fn total_assets(&self) -> &Series {
+        match self {
+            BalanceSheet::NonFinancial(balance) => &balance.total_assets,
+            BalanceSheet::Bank(balance) => &balance.total_assets,
+            BalanceSheet::Insurance(balance) => &balance.total_assets,
+        }
+    }
+
+    /// Total liabilities
+    pub fn total_liabilities(&self) -> &Series {
+        match self {
+            BalanceSheet::NonFinancial(balance) => &balance.total_liabilities,
+            BalanceSheet::Bank(balance) => &balance.total_liabilities,
+            BalanceSheet::Insurance(balance) => &balance.total_liabilities,
+        }
+    }
+
+    /// Total stockholders equity
+    pub fn total_equity(&self) -> &Series {
+        match self {
+            BalanceSheet::NonFinancial(balance) => &balance.total_equity,
+            BalanceSheet::Bank(balance) => &balance.total_equity,
+            BalanceSheet::Insurance(balance) => &balance.total_equity,
+        }
+    }
+
+    /// Long-term debt including capital lease obligations
+    pub fn long_term_debt(&self) -> &Series {
+        match self {
+            BalanceSheet::NonFinancial(balance) => &balance.long_term_debt,
+            BalanceSheet::Bank(balance) => &balance.long_term_debt,
+            BalanceSheet::Insurance(balance) => &balance.long_term_debt,
+        }
+    }
+}
+
+/// Balance sheet of non-financial companies
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct NonFinancialBalanceSheet {
+    #[serde(rename = "Cash, Cash Equivalents, Marketable Securities", default)]
+    pub cash: Series,
+    #[serde(rename = "Accounts Receivable", default)]
+    pub accounts_receivable: Series,
+    #[serde(rename = "Total Inventories", default)]
+    pub total_inventories: Series,
+    #[serde(rename = "Total Current Assets", default)]
+    pub total_current_assets: Series,
+    #[serde(rename = "Goodwill", default)]
+    pub goodwill: Series,
+    #[serde(rename = "Intangible Assets", default)]
+    pub intangible_assets: Series,
+    #[serde(rename = "Total Assets", default)]
+    pub total_assets: Series,
+    #[serde(rename = "Short-Term Debt & Capital Lease Obligation", default)]
+    pub short_term_debt: Series,
+    #[serde(rename = "Total Current Liabilities", default)]
+    pub total_current_liabilities: Series,
+    #[serde(rename = "Long-Term Debt & Capital Lease Obligation", default)]
+    pub long_term_debt: Series,
+    #[serde(rename = "Total Liabilities", default)]
+    pub total_liabilities: Series,
+    #[serde(rename = "Total Stockholders Equity", default)]
+    pub total_equity: Series,
+    /// Line items without a field of their own
+    #[serde(flatten)]
+    pub extra: Extra<NonFinancialBalanceSheet>,
+}
+
+/// Balance sheet of banks
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct BankBalanceSheet {
+    #[serde(rename = "Cash And Due From Banks", default)]
+    pub cash_and_due_from_banks: Series,
+    #[serde(rename = "Securities & Investments", default)]
+    pub securities_and_investments: Series,
+    #[serde(rename = "Net Loan", default)]
+    pub net_loans: Series,
+    #[serde(rename = "Total Assets", default)]
+    pub total_assets: Series,
+    #[serde(rename = "Total Deposits", default)]
+    pub total_deposits: Series,
+    #[serde(rename = "Short-Term Debt & Capital Lease Obligation", default)]
+    pub short_term_debt: Series,
+    #[serde(rename = "Long-Term Debt & Capital Lease Obligation", default)]
+    pub long_term_debt: Series,
+    #[serde(rename = "Total Liabilities", default)]
+    pub total_liabilities: Series,
+    #[serde(rename = "Total Stockholders Equity", default)]
+    pub total_equity: Series,
+    /// Line items without a field of their own
+    #[serde(flatten)]
+    pub extra: Extra<BankBalanceSheet>,
+}
+
+/// Balance sheet of insurance companies
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct InsuranceBalanceSheet {
+    #[serde(rename = "Cash, Cash Equivalents, Marketable Securities", default)]
+    pub cash: Series,
+    #[serde(rename = "Total Investments", default)]
+    pub total_investments: Series,
+    #[serde(rename = "Total Assets", default)]
+    pub total_assets: Series,
+    #[serde(rename = "Unpaid Loss & Loss Reserve", default)]
+    pub unpaid_loss_reserve: Series,
+    #[serde(rename = "Unearned Premiums", default)]
+    pub unearned_premiums: Series,
+    #[serde(rename = "Future Policy Benefits", default)]
+    pub future_policy_benefits: Series,
+    #[serde(rename = "Long-Term Debt & Capital Lease Obligation", default)]
+    pub long_term_debt: Series,
+    #[serde(rename = "Total Liabilities", default)]
+    pub total_liabilities: Series,
+    #[serde(rename = "Total Stockholders Equity", default)]
+    pub total_equity: Series,
+    /// Line items without a field of their own
+    #[serde(flatten)]
+    pub extra: Extra<InsuranceBalanceSheet>,
+}
+
+/// Cash flow statement according to the direct or indirect method
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum CashFlow {
+    Indirect(IndirectCashFlow),
+    Direct(DirectCashFlow),
+}
+
+impl CashFlow {
+    /// Cash flow from operating activities
+    pub fn operating_cash_flow(&self) -> &Series {
+        match self {
+            CashFlow::Indirect(cash_flow) => &cash_flow.operating_cash_flow,
+            CashFlow::Direct(cash_flow) => &cash_flow.operating_cash_flow,
+        }
+    }
+
+    /// Capital expenditure, usually given as negative value
+    pub fn capital_expenditure(&self) -> &Series {
+        match self {
+            CashFlow::Indirect(cash_flow) => &cash_flow.capital_expenditure,
+            CashFlow::Direct(cash_flow) => &cash_flow.capital_expenditure,
+        }
+    }
+
+    /// Free cash flow
+    pub fn free_cash_flow(&self) -> &Series {
+        match self {
+            CashFlow::Indirect(cash_flow) => &cash_flow.free_cash_flow,
+            CashFlow::Direct(cash_flow) => &cash_flow.free_cash_flow,
+        }
+    }
+
+    /// Dividends paid, usually given as negative value
+    pub fn dividends(&self) -> &Series {
+        match self {
+            CashFlow::Indirect(cash_flow) => &cash_flow.dividends,
+            CashFlow::Direct(cash_flow) => &cash_flow.dividends,
+        }
+    }
+}
+
+/// Cash flow statement deriving the operating cash flow from net income
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct IndirectCashFlow {
+    #[serde(rename = "Net Income From Continuing Operations", default)]
+    pub net_income: Series,
+    #[serde(rename = "Depreciation, Depletion and Amortization", default)]
+    pub depreciation: Series,
+    #[serde(rename = "Change In Working Capital", default)]
+    pub change_in_working_capital: Series,
+    #[serde(rename = "Stock Based Compensation", default)]
+    pub stock_based_compensation: Series,
+    #[serde(rename = "Cash Flow from Operations", default)]
+    pub operating_cash_flow: Series,
+    #[serde(rename = "Capital Expenditure", default)]
+    pub capital_expenditure: Series,
+    #[serde(rename = "Cash Flow from Investing", default)]
+    pub investing_cash_flow: Series,
+    #[serde(rename = "Cash Flow from Financing", default)]
+    pub financing_cash_flow: Series,
+    #[serde(rename = "Cash Flow for Dividends", default)]
+    pub dividends: Series,
+    #[serde(rename = "Repurchase of Stock", default)]
+    pub repurchase_of_stock: Series,
+    #[serde(rename = "Free Cash Flow", default)]
+    pub free_cash_flow: Series,
+    /// Line items without a field of their own
+    #[serde(flatten)]
+    pub extra: Extra<IndirectCashFlow>,
+}
+
+/// Cash flow statement listing the operating cash receipts and payments
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct DirectCashFlow {
+    #[serde(rename = "Cash Receipts from Customers", default)]
+    pub receipts_from_customers: Series,
+    #[serde(rename = "Cash Payments to Suppliers and Employees", default)]
+    pub payments_to_suppliers: Series,
+    #[serde(rename = "Cash Paid for Interest", default)]
+    pub interest_paid: Series,
+    #[serde(rename = "Cash Paid for Taxes", default)]
+    pub taxes_paid: Series,
+    #[serde(rename = "Cash Flow from Operations", default)]
+    pub operating_cash_flow: Series,
+    #[serde(rename = "Capital Expenditure", default)]
+    pub capital_expenditure: Series,
+    #[serde(rename = "Cash Flow from Investing", default)]
+    pub investing_cash_flow: Series,
+    #[serde(rename = "Cash Flow from Financing", default)]
+    pub financing_cash_flow: Series,
+    #[serde(rename = "Cash Flow for Dividends", default)]
+    pub dividends: Series,
+    #[serde(rename = "Repurchase of Stock", default)]
+    pub repurchase_of_stock: Series,
+    #[serde(rename = "Free Cash Flow", default)]
+    pub free_cash_flow: Series,
+    /// Line items without a field of their own
+    #[serde(flatten)]
+    pub extra: Extra<DirectCashFlow>,
+}
+
+/// Figures reported for REITs in addition to the regular statements
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ReitData {
+    /// Funds from operations, taken from the income statement
+    pub ffo: Series,
+    /// Funds from operations per share, taken from the per share data
+    pub ffo_per_share: Series,
+}
+
+// REIT figures are spread over two sections; all other line items of these sections
+// are ignored here, since they are part of the regular statements.
+#[derive(Deserialize)]
+struct ReitIncome {
+    #[serde(rename = "FFO", default)]
+    ffo: Series,
+}
+
+#[derive(Deserialize)]
+struct ReitPerShare {
+    #[serde(rename = "FFO per Share", default)]
+    ffo_per_share: Series,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::replay::{assert_round_trip, fixture_connector};
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn statements_from_fixture() {
+        let gf_connect = fixture_connector();
+        let financials = gf_connect.financials("NAS:MSFT").await.unwrap().financials;
+        let annuals = financials.annual_statements().unwrap();
+        assert_eq!(annuals.template, IndustryTemplate::NonFinancial);
+        assert_eq!(annuals.periods.len(), 6);
+        assert_eq!(annuals.income_statement.revenue()[4].value(), 211915.0);
+        assert_eq!(annuals.income_statement.net_income()[5].value(), 82541.0);
+        assert_eq!(annuals.income_statement.pretax_income()[4].value(), 89311.0);
+        assert_eq!(annuals.per_share.eps_diluted[5].value(), 11.06);
+        assert_eq!(annuals.per_share.dividends[0].value(), 1.84);
+        assert_eq!(annuals.per_share.month_end_price[4].value(), 340.54);
+        assert!(annuals.per_share.extra.is_empty());
+        assert_eq!(annuals.balance_sheet.total_assets()[0].value(), 286556.0);
+        assert_eq!(
+            annuals.cashflow_statement.operating_cash_flow()[3].value(),
+            89035.0
+        );
+        assert_eq!(
+            annuals.cashflow_statement.capital_expenditure()[1].value(),
+            -15441.0
+        );
+        assert_eq!(
+            annuals.cashflow_statement.free_cash_flow()[2].value(),
+            56118.0
+        );
+        assert_eq!(annuals.cashflow_statement.dividends()[5].value(), -21040.0);
+        assert_eq!(
+            annuals.balance_sheet.total_liabilities()[0].value(),
+            184226.0
+        );
+        assert_eq!(annuals.balance_sheet.total_equity()[0].value(), 102330.0);
+        assert_eq!(annuals.balance_sheet.long_term_debt()[0].value(), 66662.0);
+        assert!(annuals.reit.is_none());
+        match &annuals.income_statement {
+            Income::NonFinancial(income) => {
+                assert_eq!(income.shares_diluted.len(), 6);
+                assert!(income.extra.is_empty());
+            }
+            _ => panic!("unexpected template"),
+        }
+        match &annuals.balance_sheet {
+            BalanceSheet::NonFinancial(balance) => assert!(balance.extra.is_empty()),
+            _ => panic!("unexpected template"),
+        }
+        match &annuals.cashflow_statement {
+            CashFlow::Indirect(cash_flow) => {
+                assert_eq!(cash_flow.stock_based_compensation[4].value(), 9611.0);
+                assert!(cash_flow.extra.is_empty());
+            }
+            _ => panic!("unexpected cash flow method"),
+        }
+        assert_round_trip(&annuals);
+
+        let quarterly = financials.quarterly_statements().unwrap();
+        assert_eq!(quarterly.income_statement.revenue()[4].value(), 62020.0);
+    }
+
+    fn period_data(sections: Value) -> PeriodData {
+        let mut data = json!({
+            "Fiscal Year": ["2022-12", "2023-12"],
+            "Preliminary": [0, 0],
+            "per_share_data_array": {},
+            "common_size_ratios": {},
+            "income_statement": {},
+            "balance_sheet": {},
+            "cashflow_statement": {},
+            "valuation_ratios": {},
+            "valuation_and_quality": {}
+        });
+        for (section, items) in sections.as_object().unwrap() {
+            data[section] = items.clone();
+        }
+        serde_json::from_value(data).unwrap()
+    }
+
+    fn parameters(template: &str, reit: &str, direct: &str) -> FinancialTemplateParameters {
+        serde_json::from_value(json!({
+            "ind_template": template,
+            "REITs": reit,
+            "IsDirect": direct,
+            "financial_report_frequency": "Quarterly"
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn bank_statements() {
+        let data = period_data(json!({
+            "income_statement": {"Net Interest Income": ["10.5", "12.0"], "Net Income": [3, 4]},
+            "balance_sheet": {"Total Deposits": ["100", "110"], "Total Assets": ["150", "160"]},
+            "cashflow_statement": {"Cash Receipts from Customers": ["20", "22"]}
+        }));
+        let statements = data.statements(&parameters("B", "N", "Y")).unwrap();
+        assert_eq!(statements.template, IndustryTemplate::Bank);
+        assert_eq!(statements.income_statement.net_income()[1].value(), 4.0);
+        match &statements.income_statement {
+            Income::Bank(income) => assert_eq!(income.net_interest_income[0].value(), 10.5),
+            _ => panic!("unexpected template"),
+        }
+        match &statements.balance_sheet {
+            BalanceSheet::Bank(balance) => assert_eq!(balance.total_deposits[1].value(), 110.0),
+            _ => panic!("unexpected template"),
+        }
+        match &statements.cashflow_statement {
+            CashFlow::Direct(cash_flow) => {
+                assert_eq!(cash_flow.receipts_from_customers[1].value(), 22.0)
+            }
+            _ => panic!("unexpected cash flow method"),
+        }
+    }
+
+    #[test]
+    fn reit_and_insurance_statements() {
+        let data = period_data(json!({
+            "per_share_data_array": {"FFO per Share": ["2.10", "2.25"]},
+            "income_statement": {"FFO": ["500", "540"], "Rental Income": ["900", "950"]},
+        }));
+        let statements = data.statements(&parameters("N", "Y", "N")).unwrap();
+        let reit = statements.reit.unwrap();
+        assert_eq!(reit.ffo_per_share[1].value(), 2.25);
+        assert_eq!(reit.ffo[0].value(), 500.0);
+        match &statements.income_statement {
+            Income::NonFinancial(income) => assert_eq!(income.extra["Rental Income"][1], "950"),
+            _ => panic!("unexpected template"),
+        }
+
+        let statements = data.statements(&parameters("I", "N", "N")).unwrap();
+        assert!(matches!(
+            statements.balance_sheet,
+            BalanceSheet::Insurance(_)
+        ));
+        let err = data.statements(&parameters("X", "N", "N")).unwrap_err();
+        assert!(matches!(err, GuruFocusError::UnsupportedTemplate { .. }));
+    }
+}
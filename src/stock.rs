@@ -1,13 +1,17 @@
+use crate::extra::Extra;
 pub use crate::hexnum::HexNum;
 pub use crate::strnum::FloatOrString;
-use serde::Deserialize;
+use crate::symbol::Symbol;
+use crate::GuruFocusError;
+use chrono::{NaiveDate, NaiveDateTime};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 type JsonObject = HashMap<String, serde_json::Value>;
 
 /// Container for basic data for a single stock
-#[derive(Deserialize, Debug)]
-#[serde(deny_unknown_fields)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct Stock {
     /// Name of the company
     pub company: String,
@@ -22,12 +26,14 @@ pub struct Stock {
     /// Main subindustry the company operates in
     pub subindustry: String,
     /// Stock ticker symbol
-    pub symbol: String,
+    pub symbol: Symbol,
+    /// Fields unknown to this crate, see `FieldMode`
+    #[serde(flatten)]
+    pub extra: Extra<Stock>,
 }
 
 /// Container for single quote data
-#[derive(Deserialize, Debug)]
-#[serde(deny_unknown_fields)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct Quote {
     /// Currency of quoted stock price
     #[serde(rename = "Currency")]
@@ -52,10 +58,11 @@ pub struct Quote {
     pub price_change: FloatOrString,
     /// Time of last price update
     #[serde(rename = "Price Updated Time")]
-    pub update_time: String,
+    #[serde(with = "crate::dates::date_time")]
+    pub update_time: NaiveDateTime,
     /// Stock ticker symbol
     #[serde(rename = "Symbol")]
-    pub symbol: String,
+    pub symbol: Symbol,
     /// Today's highest price
     pub high: FloatOrString,
     /// Today's lowest price
@@ -64,18 +71,87 @@ pub struct Quote {
     pub open: FloatOrString,
     /// Timestamp
     pub timestamp: i64,
+    /// Fields unknown to this crate, see `FieldMode`
+    #[serde(flatten)]
+    pub extra: Extra<Quote>,
 }
 
 /// Container for summary of basic stock data and valuation ratios
-#[derive(Deserialize, Debug)]
-#[serde(deny_unknown_fields)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct StockSummary {
     pub summary: StockSummaryByCat,
+    /// Fields unknown to this crate, see `FieldMode`
+    #[serde(flatten)]
+    pub extra: Extra<StockSummary>,
+}
+
+/// The entries of `ratio` and `company_data` are kept as plain JSON when the summary is
+/// parsed, so the `FieldMode` of the connector does not apply to them. The accessors below
+/// always decode leniently: fields unknown to the returned struct end up in its `extra`
+/// member instead of causing an error.
+impl StockSummary {
+    /// Returns the comparison of a ratio with its history and the industry, e.g.
+    /// `ratio_cmp("P/E(ttm)")`
+    pub fn ratio_cmp(&self, name: &str) -> Result<RatioCmp, GuruFocusError> {
+        decode_entry(&self.summary.ratio, "ratio", name)
+    }
+
+    /// Returns the range of a rank, e.g. `ratio_range("Financial Strength")`
+    pub fn ratio_range(&self, name: &str) -> Result<RatioRange, GuruFocusError> {
+        decode_entry(&self.summary.ratio, "ratio", name)
+    }
+
+    /// Returns the warning signs
+    pub fn warnings(&self) -> Result<Vec<WarningDetails>, GuruFocusError> {
+        decode_entry(&self.summary.ratio, "ratio", "warning_signs")
+    }
+
+    /// Returns the good signs
+    pub fn good_signs(&self) -> Result<Vec<GoodDetails>, GuruFocusError> {
+        decode_entry(&self.summary.ratio, "ratio", "good_signs")
+    }
+
+    /// Returns address and description of the company
+    pub fn description(&self) -> Result<CompanyDescription, GuruFocusError> {
+        decode_entry(&self.summary.company_data, "company_data", "descrpt")
+    }
+
+    /// Returns country and exchange of the stock
+    pub fn country(&self) -> Result<Country, GuruFocusError> {
+        decode_entry(&self.summary.company_data, "company_data", "country")
+    }
+
+    /// Returns the price changes of the last trading day
+    pub fn stock_dynamics(&self) -> Result<StockDynamics, GuruFocusError> {
+        decode_entry(&self.summary.company_data, "company_data", "stock")
+    }
+
+    /// Returns sector, industry and group of the company
+    pub fn industry(&self) -> Result<IndustryDetails, GuruFocusError> {
+        decode_entry(&self.summary.company_data, "company_data", "industry")
+    }
+}
+
+/// Decode entry `key` of one of the maps of `StockSummaryByCat`, keeping unknown fields
+/// in the `extra` member of the result
+fn decode_entry<T: DeserializeOwned>(
+    map: &JsonObject,
+    map_name: &str,
+    key: &str,
+) -> Result<T, GuruFocusError> {
+    let entry = format!("{}.{}", map_name, key);
+    let value = map.get(key).ok_or_else(|| GuruFocusError::MissingEntry {
+        entry: entry.clone(),
+    })?;
+    serde_json::from_value(value.clone()).map_err(|source| GuruFocusError::InvalidEntry {
+        entry,
+        type_name: std::any::type_name::<T>(),
+        source,
+    })
 }
 
 /// Container for summary of basic stock data by category
-#[derive(Deserialize, Debug)]
-#[serde(deny_unknown_fields)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct StockSummaryByCat {
     pub general: GeneralData,
     pub chart: Chart,
@@ -84,31 +160,37 @@ pub struct StockSummaryByCat {
     pub insider: HashMap<String, InsiderTransaction>,
     pub company_data: JsonObject,
     pub estimate: Estimate,
+    /// Fields unknown to this crate, see `FieldMode`
+    #[serde(flatten)]
+    pub extra: Extra<StockSummaryByCat>,
 }
 
 /// Container for transaction done by Gurus
-#[derive(Deserialize, Debug)]
-#[serde(deny_unknown_fields)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct GuruTransaction {
     pub buy: i64,
     pub hold: i64,
     pub new_buy: i64,
     pub sell: i64,
     pub sold_out: i64,
+    /// Fields unknown to this crate, see `FieldMode`
+    #[serde(flatten)]
+    pub extra: Extra<GuruTransaction>,
 }
 
 /// Container for transaction done by Insiders
-#[derive(Deserialize, Debug)]
-#[serde(deny_unknown_fields)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct InsiderTransaction {
     pub buy: FloatOrString,
     pub sell: Option<FloatOrString>,
     pub price: Option<FloatOrString>,
+    /// Fields unknown to this crate, see `FieldMode`
+    #[serde(flatten)]
+    pub extra: Extra<InsiderTransaction>,
 }
 
 /// Chart
-#[derive(Deserialize, Debug)]
-#[serde(deny_unknown_fields)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct Chart {
     #[serde(rename = "Graham Number")]
     pub graham_number: FloatOrString,
@@ -132,11 +214,13 @@ pub struct Chart {
     pub gf_balue: FloatOrString,
     #[serde(rename = "Earnings Power Value")]
     pub earnings_power_value: FloatOrString,
+    /// Fields unknown to this crate, see `FieldMode`
+    #[serde(flatten)]
+    pub extra: Extra<Chart>,
 }
 
 /// Estimate summary
-#[derive(Deserialize, Debug)]
-#[serde(deny_unknown_fields)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct Estimate {
     #[serde(rename = "LongTermGrowthRateMean")]
     pub longtermgrowthratemean: FloatOrString,
@@ -156,11 +240,13 @@ pub struct Estimate {
     pub quarter: Vec<FloatOrString>,
     #[serde(rename = "LongTermRevenueGrowthRateMean")]
     pub longtermrevenuegrowthratemean: FloatOrString,
+    /// Fields unknown to this crate, see `FieldMode`
+    #[serde(flatten)]
+    pub extra: Extra<Estimate>,
 }
 
 /// General data for summary
-#[derive(Deserialize, Debug)]
-#[serde(deny_unknown_fields)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct GeneralData {
     pub company: String,
     pub desc: String,
@@ -175,7 +261,8 @@ pub struct GeneralData {
     pub country: String,
     pub group: String,
     pub subindustry: String,
-    pub timestamp: String,
+    #[serde(with = "crate::dates::date_time")]
+    pub timestamp: NaiveDateTime,
     pub gf_score: FloatOrString,
     pub rank_gf_value: FloatOrString,
     pub rank_growth: FloatOrString,
@@ -198,91 +285,109 @@ pub struct GeneralData {
     pub percentage_of_premiumplus_guru_buys: FloatOrString,
     pub percentage_of_premiumplus_guru_sells: FloatOrString,
     pub percentage_of_premiumplus_guru_holds: FloatOrString,
+    /// Fields unknown to this crate, see `FieldMode`
+    #[serde(flatten)]
+    pub extra: Extra<GeneralData>,
 }
 
 /// Ratio comparison
-#[derive(Deserialize, Debug)]
-#[serde(deny_unknown_fields)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct RatioCmp {
     pub his: HistoryCmp,
     pub indu: IndustryCmp,
     pub status: FloatOrString,
     pub value: FloatOrString,
+    /// Fields unknown to this crate, see `FieldMode`
+    #[serde(flatten)]
+    pub extra: Extra<RatioCmp>,
 }
 
 /// Comparison to historic data
-#[derive(Deserialize, Debug)]
-#[serde(deny_unknown_fields)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct HistoryCmp {
     pub high: FloatOrString,
     pub low: FloatOrString,
     #[serde(default)]
     pub med: FloatOrString,
+    /// Fields unknown to this crate, see `FieldMode`
+    #[serde(flatten)]
+    pub extra: Extra<HistoryCmp>,
 }
 
 /// Comparison to industry
-#[derive(Deserialize, Debug)]
-#[serde(deny_unknown_fields)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct IndustryCmp {
     pub global_rank: FloatOrString,
     pub indu_med: FloatOrString,
     pub indu_tot: FloatOrString,
+    /// Fields unknown to this crate, see `FieldMode`
+    #[serde(flatten)]
+    pub extra: Extra<IndustryCmp>,
 }
 
 /// Description of range of some ratio
-#[derive(Deserialize, Debug)]
-#[serde(deny_unknown_fields)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct RatioRange {
     pub color: HexNum,
     pub current: FloatOrString,
     pub high: FloatOrString,
     pub low: FloatOrString,
+    /// Fields unknown to this crate, see `FieldMode`
+    #[serde(flatten)]
+    pub extra: Extra<RatioRange>,
 }
 
 /// Details to a specific warning
-#[derive(Deserialize, Debug)]
-#[serde(deny_unknown_fields)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct WarningDetails {
     pub category: Option<String>,
     pub degree: String,
     pub details: String,
     pub display: String,
     pub name: String,
+    /// Fields unknown to this crate, see `FieldMode`
+    #[serde(flatten)]
+    pub extra: Extra<WarningDetails>,
 }
 
 /// Details to a specific warning
-#[derive(Deserialize, Debug)]
-#[serde(deny_unknown_fields)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct GoodDetails {
     pub category: Option<String>,
     pub details: String,
     pub display: String,
     pub name: String,
+    /// Fields unknown to this crate, see `FieldMode`
+    #[serde(flatten)]
+    pub extra: Extra<GoodDetails>,
 }
 /// Address and related company information
-#[derive(Deserialize, Debug)]
-#[serde(deny_unknown_fields)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct CompanyDescription {
     pub address: String,
     pub descrpt: String,
     pub morn_comp_id: Option<String>,
     pub short_descript: String,
-    pub symbol: Option<String>,
+    pub symbol: Option<Symbol>,
     pub website: String,
+    /// Fields unknown to this crate, see `FieldMode`
+    #[serde(flatten)]
+    pub extra: Extra<CompanyDescription>,
 }
 
 /// Country and exchange information
-#[derive(Deserialize, Debug)]
-#[serde(deny_unknown_fields)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct Country {
     pub country: String,
     pub exchange: String,
-    pub symbol: String,
+    pub symbol: Symbol,
+    /// Fields unknown to this crate, see `FieldMode`
+    #[serde(flatten)]
+    pub extra: Extra<Country>,
 }
 
 /// Stock price change information
-#[derive(Deserialize, Debug)]
-#[serde(deny_unknown_fields)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct StockDynamics {
     pub high: FloatOrString,
     pub low: FloatOrString,
@@ -292,11 +397,13 @@ pub struct StockDynamics {
     pub price: FloatOrString,
     pub stockid: Option<String>,
     pub volumn_day: FloatOrString,
+    /// Fields unknown to this crate, see `FieldMode`
+    #[serde(flatten)]
+    pub extra: Extra<StockDynamics>,
 }
 
 /// Industry detail information
-#[derive(Deserialize, Debug)]
-#[serde(deny_unknown_fields)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct IndustryDetails {
     pub group: String,
     pub groupcode: i64,
@@ -304,29 +411,166 @@ pub struct IndustryDetails {
     pub industrycode: i64,
     pub sector: String,
     pub sectorcode: i64,
-    pub date: String,
+    #[serde(with = "crate::dates::date")]
+    pub date: NaiveDate,
+    /// Fields unknown to this crate, see `FieldMode`
+    #[serde(flatten)]
+    pub extra: Extra<IndustryDetails>,
 }
 
 /// Historic dividend data
-#[derive(Deserialize, Debug)]
-#[serde(deny_unknown_fields)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct Dividend {
-    pub ex_date: String,
-    pub record_date: String,
+    #[serde(with = "crate::dates::date")]
+    pub ex_date: NaiveDate,
+    #[serde(default, with = "crate::dates::option_date")]
+    pub record_date: Option<NaiveDate>,
     pub amount: FloatOrString,
-    pub pay_date: String,
+    #[serde(default, with = "crate::dates::option_date")]
+    pub pay_date: Option<NaiveDate>,
     pub currency: String,
     #[serde(rename = "type")]
     pub div_type: String,
+    /// Fields unknown to this crate, see `FieldMode`
+    #[serde(flatten)]
+    pub extra: Extra<Dividend>,
+}
+
+impl Dividend {
+    /// Returns true for special (i.e. non-recurring) dividends, based on `div_type`
+    pub fn is_special(&self) -> bool {
+        self.div_type.to_lowercase().contains("special")
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::super::*;
-    use super::*;
+    use crate::replay::{assert_round_trip, fixture_connector};
     use chrono::{Datelike, NaiveDate, Utc};
     use std::env;
 
+    #[tokio::test]
+    async fn quotes_from_fixture() {
+        let gf_connect = fixture_connector();
+        let quotes = gf_connect.quotes(&["NAS:MSFT", "NAS:AAPL"]).await.unwrap();
+        assert_eq!(quotes.len(), 2);
+        assert_eq!(quotes[0].symbol, "MSFT");
+        assert_eq!(quotes[0].qualified_symbol().to_string(), "NAS:MSFT");
+        // symbols are normalized before sending the request
+        let symbols = [Symbol::from("nas:msft"), "NAS:AAPL".parse().unwrap()];
+        assert_eq!(gf_connect.quotes(&symbols).await.unwrap().len(), 2);
+        assert_eq!(quotes[1].price.value(), 192.53);
+        assert_round_trip(&quotes);
+    }
+
+    #[tokio::test]
+    async fn price_history_from_fixture() {
+        let gf_connect = fixture_connector();
+        let prices = gf_connect.price_hist("NAS:AAPL").await.unwrap();
+        let unadjusted = gf_connect.unadj_price_hist("NAS:AAPL").await.unwrap();
+        assert_eq!(prices.len(), unadjusted.len());
+        assert_eq!(
+            prices.first().map(|(date, _)| date),
+            NaiveDate::from_ymd_opt(2020, 7, 27)
+        );
+        // prices before the 4:1 split are adjusted
+        assert!(prices.points()[0].1 < unadjusted.points()[0].1 / 4.0);
+        assert_eq!(prices.last(), unadjusted.last());
+        assert_round_trip(&unadjusted);
+    }
+
+    #[tokio::test]
+    async fn stock_summary_from_fixture() {
+        let gf_connect = fixture_connector();
+        let summary = gf_connect.stock_summary("NAS:MSFT").await.unwrap().summary;
+        assert_eq!(summary.general.company, "Microsoft Corp");
+        assert_eq!(summary.chart.graham_number.value(), 89.32);
+        assert_eq!(summary.guru["2023-09"].buy, 11);
+        assert!(summary.insider["2023-11"].sell.is_none());
+        assert_eq!(summary.estimate.eps_nri.len(), 3);
+        assert_round_trip(&summary);
+    }
+
+    #[tokio::test]
+    async fn stock_summary_entries() {
+        let gf_connect = fixture_connector();
+        let summary = gf_connect.stock_summary("NAS:MSFT").await.unwrap();
+        let pe = summary.ratio_cmp("P/E(ttm)").unwrap();
+        assert_eq!(pe.value.value(), 34.0);
+        assert_eq!(pe.his.med.value(), 32.04);
+        assert_eq!(pe.indu.indu_tot.value(), 1807.0);
+        let strength = summary.ratio_range("Financial Strength").unwrap();
+        assert_eq!(strength.current.value(), 7.0);
+        let warnings = summary.warnings().unwrap();
+        assert_eq!(warnings.len(), 2);
+        assert_eq!(warnings[0].degree, "medium");
+        assert!(warnings[1].category.is_none());
+        assert_eq!(summary.good_signs().unwrap()[1].name, "interest_coverage");
+        assert_eq!(
+            summary.description().unwrap().website,
+            "https://www.microsoft.com"
+        );
+        assert_eq!(
+            summary.country().unwrap().qualified_symbol().to_string(),
+            "NAS:MSFT"
+        );
+        assert_eq!(summary.stock_dynamics().unwrap().price.value(), 376.04);
+        assert_eq!(summary.industry().unwrap().sectorcode, 311);
+
+        match summary.ratio_cmp("P/S").unwrap_err() {
+            GuruFocusError::MissingEntry { entry } => assert_eq!(entry, "ratio.P/S"),
+            err => panic!("unexpected error {:?}", err),
+        }
+        match summary.ratio_cmp("Financial Strength").unwrap_err() {
+            GuruFocusError::InvalidEntry { entry, .. } => {
+                assert_eq!(entry, "ratio.Financial Strength")
+            }
+            err => panic!("unexpected error {:?}", err),
+        }
+    }
+
+    #[tokio::test]
+    async fn stock_summary_entries_are_lenient() {
+        let gf_connect = fixture_connector();
+        let mut summary = gf_connect.stock_summary("NAS:MSFT").await.unwrap();
+        summary.summary.company_data.get_mut("country").unwrap()["currency"] = "USD".into();
+        let country = summary.country().unwrap();
+        assert_eq!(country.extra["currency"], "USD");
+    }
+
+    #[tokio::test]
+    async fn stock_list_from_fixture() {
+        let gf_connect = fixture_connector();
+        let stocks = gf_connect.listed_stocks("OSL").await.unwrap();
+        assert_eq!(stocks[0].symbol, "EQNR");
+        assert!(stocks.iter().all(|s| s.currency == "NOK"));
+        assert_round_trip(&stocks);
+    }
+
+    #[tokio::test]
+    async fn dividend_history_from_fixture() {
+        let gf_connect = fixture_connector();
+        let dividends = gf_connect.dividend_history("NAS:MSFT").await.unwrap();
+        assert_eq!(dividends.len(), 42);
+        assert_eq!(
+            dividends[0].ex_date,
+            NaiveDate::from_ymd_opt(2023, 11, 15).unwrap()
+        );
+        assert_eq!(dividends[0].pay_date, NaiveDate::from_ymd_opt(2023, 12, 13));
+        assert_eq!(dividends[0].amount.value(), 0.75);
+        assert_eq!(dividends[41].div_type, "Cash Div.");
+        assert_round_trip(&dividends);
+    }
+
+    #[tokio::test]
+    async fn fundamental_updates_from_fixture() {
+        let gf_connect = fixture_connector();
+        let date = NaiveDate::from_ymd_opt(2023, 12, 1).unwrap();
+        let stocks = gf_connect.updated_stocks(date).await.unwrap();
+        assert!(stocks.contains(&Symbol::from("NAS:MSFT")));
+    }
+
     #[tokio::test]
     async fn test_quotes() {
         if let Ok(token) = env::var("GURUFOCUS_TOKEN") {
@@ -337,7 +581,20 @@ mod tests {
                 assert!(prices.is_ok());
                 let prices = serde_json::from_value::<Vec<Quote>>(prices.unwrap());
                 assert!(prices.is_ok());
-                assert!(prices.unwrap().len() > 0);
+                assert!(!prices.unwrap().is_empty());
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_quotes_typed() {
+        if let Ok(token) = env::var("GURUFOCUS_TOKEN") {
+            if !token.is_empty() {
+                let gf_connect = GuruFocusConnector::new(token);
+                let ticker = ["NAS:AAPL", "FRA:APC", "LTS:0JQ4"];
+                let prices = gf_connect.quotes(&ticker).await;
+                assert!(prices.is_ok());
+                assert!(!prices.unwrap().is_empty());
             }
         }
     }
@@ -352,7 +609,20 @@ mod tests {
                 assert!(prices.is_ok());
                 let prices = serde_json::from_value::<Vec<(String, f64)>>(prices.unwrap());
                 assert!(prices.is_ok());
-                assert!(prices.unwrap().len() > 0);
+                assert!(!prices.unwrap().is_empty());
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_quote_history_typed() {
+        if let Ok(token) = env::var("GURUFOCUS_TOKEN") {
+            if !token.is_empty() {
+                let gf_connect = GuruFocusConnector::new(token);
+                let stock = "NYSE:DIS";
+                let prices = gf_connect.price_hist(stock).await;
+                assert!(prices.is_ok());
+                assert!(!prices.unwrap().is_empty());
             }
         }
     }
@@ -367,7 +637,20 @@ mod tests {
                 assert!(prices.is_ok());
                 let prices = serde_json::from_value::<Vec<(String, f64)>>(prices.unwrap());
                 assert!(prices.is_ok());
-                assert!(prices.unwrap().len() > 0);
+                assert!(!prices.unwrap().is_empty());
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_quote_history_unadj_typed() {
+        if let Ok(token) = env::var("GURUFOCUS_TOKEN") {
+            if !token.is_empty() {
+                let gf_connect = GuruFocusConnector::new(token);
+                let stock = "NYSE:DIS";
+                let prices = gf_connect.unadj_price_hist(stock).await;
+                assert!(prices.is_ok());
+                assert!(!prices.unwrap().is_empty());
             }
         }
     }
@@ -387,6 +670,18 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_stock_summary_typed() {
+        if let Ok(token) = env::var("GURUFOCUS_TOKEN") {
+            if !token.is_empty() {
+                let gf_connect = GuruFocusConnector::new(token);
+                let stock = "NYSE:BAC";
+                let stock_summary = gf_connect.stock_summary(stock).await;
+                assert!(stock_summary.is_ok());
+            }
+        }
+    }
+
     #[tokio::test]
     async fn test_stock_list() {
         if let Ok(token) = env::var("GURUFOCUS_TOKEN") {
@@ -402,6 +697,19 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_stock_list_typed() {
+        if let Ok(token) = env::var("GURUFOCUS_TOKEN") {
+            if !token.is_empty() {
+                let gf_connect = GuruFocusConnector::new(token);
+                // Get all stocks listed at the Oslo stock exchange (OSL) in Norway
+                let exchange = "OSL";
+                let stocks = gf_connect.listed_stocks(exchange).await;
+                assert!(stocks.is_ok());
+            }
+        }
+    }
+
     #[tokio::test]
     async fn test_dividend_history() {
         if let Ok(token) = env::var("GURUFOCUS_TOKEN") {
@@ -416,6 +724,18 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_dividend_history_typed() {
+        if let Ok(token) = env::var("GURUFOCUS_TOKEN") {
+            if !token.is_empty() {
+                let gf_connect = GuruFocusConnector::new(token);
+                let stock = "NAS:MSFT";
+                let dividends = gf_connect.dividend_history(stock).await;
+                assert!(dividends.is_ok());
+            }
+        }
+    }
+
     fn get_days_from_month(year: i32, month: u32) -> u32 {
         NaiveDate::from_ymd_opt(
             match month {
@@ -466,4 +786,18 @@ mod tests {
             }
         }
     }
+
+    #[tokio::test]
+    async fn test_fundamental_updates_typed() {
+        if let Ok(token) = env::var("GURUFOCUS_TOKEN") {
+            if !token.is_empty() {
+                let gf_connect = GuruFocusConnector::new(token);
+
+                let now = Utc::now().naive_local().date();
+                let one_months_ago = month_before(now, 6);
+                let stocks = gf_connect.updated_stocks(one_months_ago).await;
+                assert!(stocks.is_ok());
+            }
+        }
+    }
 }
@@ -1,135 +1,255 @@
+//! Numbers returned by GuruFocus either as JSON numbers or as strings. Strings may carry
+//! thousands separators, a `$` sign, a `%` sign, a `K`/`M`/`B`/`T` multiplier or
+//! parentheses for negative values, and some are not numbers at all, but a note like
+//! "Negative Tangible Equity" explaining why a value is not available.
+//!
+//! Before version 0.8, `FloatOrString` was a tuple struct holding an `f64`, with missing
+//! values and notes mapped to `NaN` (empty strings to `0.0`). Code using the former field
+//! `.0` can use `value()` instead, which still returns `NaN` in these cases.
+
 use std::default;
 use std::fmt;
-use std::marker::PhantomData;
 use std::str::FromStr;
 
 use serde::de::{self, Visitor};
-use serde::{Deserialize, Deserializer};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Number which may be missing or replaced by an explanatory text
+#[derive(Debug, Clone, PartialEq)]
+pub enum FloatOrString {
+    /// Numeric value
+    Value(f64),
+    /// Numeric value given with percent sign, e.g. `12.5` for "12.5%"
+    Percent(f64),
+    /// No value available, i.e. `null`, an empty string or a placeholder like "N/A"
+    Missing,
+    /// Text given instead of a number, e.g. "Negative Tangible Equity"
+    Text(String),
+}
+
+impl FloatOrString {
+    /// Returns the number, if available; percentages are returned as given, e.g. `12.5`
+    /// for "12.5%"
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            FloatOrString::Value(x) | FloatOrString::Percent(x) => Some(*x),
+            _ => None,
+        }
+    }
+
+    /// Returns the number, or `NaN` if not available, like the former field `.0`
+    pub fn value(&self) -> f64 {
+        self.as_f64().unwrap_or(f64::NAN)
+    }
+
+    /// Returns the number, or the given default if not available
+    pub fn unwrap_or(&self, default: f64) -> f64 {
+        self.as_f64().unwrap_or(default)
+    }
+
+    /// Returns true if no value is available
+    pub fn is_missing(&self) -> bool {
+        *self == FloatOrString::Missing
+    }
+
+    /// Returns true if the value has been given with percent sign
+    pub fn is_percent(&self) -> bool {
+        matches!(self, FloatOrString::Percent(_))
+    }
 
-#[derive(Debug, Clone, Copy)]
-pub struct FloatOrString(f64);
+    /// Returns the text given instead of a number, if any
+    pub fn note(&self) -> Option<&str> {
+        match self {
+            FloatOrString::Text(note) => Some(note),
+            _ => None,
+        }
+    }
+}
 
 impl<'de> Deserialize<'de> for FloatOrString {
     fn deserialize<D>(deserializer: D) -> Result<FloatOrString, D::Error>
     where
         D: Deserializer<'de>,
     {
-        // This is a Visitor that forwards string types to T's `FromStr` impl and
-        // forwards number types to T's `Deserialize` impl. The `PhantomData` is to
-        // keep the compiler from complaining about T being an unused generic type
-        // parameter. We need T in order to know the Value type for the Visitor
-        // impl.
-        struct StringOrNum<T>(PhantomData<fn() -> T>);
-
-        impl<'de, T> Visitor<'de> for StringOrNum<T>
-        where
-            T: Deserialize<'de>
-                + FromStr<Err = <f64 as FromStr>::Err>
-                + From<u64>
-                + From<i64>
-                + From<f64>,
-        {
-            type Value = T;
+        // Visitor forwarding strings to the `FromStr` implementation and accepting
+        // any JSON number.
+        struct StringOrNum;
+
+        impl<'de> Visitor<'de> for StringOrNum {
+            type Value = FloatOrString;
 
             fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
                 formatter.write_str("string or number")
             }
 
-            fn visit_str<E>(self, value: &str) -> Result<T, E>
+            fn visit_str<E>(self, value: &str) -> Result<FloatOrString, E>
             where
                 E: de::Error,
             {
-                Ok(FromStr::from_str(value).unwrap())
+                Ok(parse_number(value))
             }
 
-            fn visit_u64<E>(self, value: u64) -> Result<T, E>
+            fn visit_u64<E>(self, value: u64) -> Result<FloatOrString, E>
             where
                 E: de::Error,
             {
-                Ok(From::from(value))
+                Ok(FloatOrString::Value(value as f64))
             }
 
-            fn visit_i64<E>(self, value: i64) -> Result<T, E>
+            fn visit_i64<E>(self, value: i64) -> Result<FloatOrString, E>
             where
                 E: de::Error,
             {
-                Ok(From::from(value))
+                Ok(FloatOrString::Value(value as f64))
             }
 
-            fn visit_f64<E>(self, value: f64) -> Result<T, E>
+            fn visit_f64<E>(self, value: f64) -> Result<FloatOrString, E>
             where
                 E: de::Error,
             {
-                Ok(From::from(value))
+                Ok(FloatOrString::from(value))
             }
 
-            fn visit_unit<E>(self) -> Result<T, E>
+            fn visit_unit<E>(self) -> Result<FloatOrString, E>
             where
                 E: de::Error,
             {
-                Ok(From::from(std::f64::NAN))
+                Ok(FloatOrString::Missing)
             }
         }
 
-        deserializer.deserialize_any(StringOrNum(PhantomData))
+        deserializer.deserialize_any(StringOrNum)
     }
 }
 
-// The `string_or_num` function uses this impl to instantiate a `FloatOrString` if
-// the input file contains a string and not a number.
-impl FromStr for FloatOrString {
-    type Err = <f64 as FromStr>::Err;
+/// Serialize into the representation read by `Deserialize`, i.e. numbers as JSON number,
+/// percentages and notes as string and missing values as empty string. Numbers which are
+/// not finite have no JSON representation and are serialized as missing values.
+impl Serialize for FloatOrString {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            FloatOrString::Value(x) | FloatOrString::Percent(x) if !x.is_finite() => {
+                serializer.serialize_str("")
+            }
+            FloatOrString::Value(x) => serializer.serialize_f64(*x),
+            FloatOrString::Percent(_) => serializer.collect_str(self),
+            FloatOrString::Missing => serializer.serialize_str(""),
+            FloatOrString::Text(note) => serializer.serialize_str(note),
+        }
+    }
+}
 
-    fn from_str(s: &str) -> Result<FloatOrString, Self::Err> {
-        if s.is_empty() {
-            Ok(FloatOrString(0.0))
-        } else {
-            // remove commas as thousands separator
-            let s = s.replace(',', "");
-            match s.parse::<f64>() {
-                Ok(num) => Ok(FloatOrString(num)),
-                // If string can not be parsed, set value to NaN
-                _ => Ok(FloatOrString(std::f64::NAN)),
+/// Parse number in any of the formats used by GuruFocus; strings which are not numbers
+/// are kept as text
+fn parse_number(s: &str) -> FloatOrString {
+    let trimmed = s.trim();
+    if matches!(
+        trimmed.to_ascii_lowercase().as_str(),
+        "" | "-" | "--" | "n/a" | "na" | "nan" | "null" | "none"
+    ) {
+        return FloatOrString::Missing;
+    }
+    let (negative, mut num) = match trimmed.strip_prefix('(').and_then(|s| s.strip_suffix(')')) {
+        Some(inner) => (true, inner.trim()),
+        None => (false, trimmed),
+    };
+    let is_percent = num.ends_with('%');
+    if is_percent {
+        num = num[..num.len() - 1].trim_end();
+    }
+    let (sign, num) = match num.strip_prefix('-') {
+        Some(rest) => (-1.0, rest),
+        None => (1.0, num),
+    };
+    let num = num.strip_prefix('$').unwrap_or(num).trim();
+    let num = num.replace(',', "");
+    let (num, multiplier) = match num.char_indices().last() {
+        Some((idx, suffix)) if !is_percent && idx > 0 => match suffix.to_ascii_uppercase() {
+            'K' => (&num[..idx], 1e3),
+            'M' => (&num[..idx], 1e6),
+            'B' => (&num[..idx], 1e9),
+            'T' => (&num[..idx], 1e12),
+            _ => (num.as_str(), 1.0),
+        },
+        _ => (num.as_str(), 1.0),
+    };
+    // reject strings like "inf" or "NaN", which are valid for `f64::from_str`
+    let is_numeric = num
+        .chars()
+        .all(|c| c.is_ascii_digit() || matches!(c, '.' | 'e' | 'E' | '+' | '-'));
+    match num.parse::<f64>() {
+        Ok(x) if is_numeric => {
+            let x = if negative { -sign * x } else { sign * x } * multiplier;
+            if is_percent {
+                FloatOrString::Percent(x)
+            } else {
+                FloatOrString::Value(x)
             }
         }
+        _ => FloatOrString::Text(trimmed.to_string()),
+    }
+}
+
+impl FromStr for FloatOrString {
+    type Err = std::convert::Infallible;
+
+    fn from_str(s: &str) -> Result<FloatOrString, Self::Err> {
+        Ok(parse_number(s))
     }
 }
 
+/// Convert into number, with `NaN` for missing values and notes
 impl From<FloatOrString> for f64 {
     fn from(fos: FloatOrString) -> f64 {
-        fos.0
+        fos.value()
+    }
+}
+
+impl From<&FloatOrString> for f64 {
+    fn from(fos: &FloatOrString) -> f64 {
+        fos.value()
     }
 }
 
 impl From<u64> for FloatOrString {
     fn from(val: u64) -> FloatOrString {
-        FloatOrString(val as f64)
+        FloatOrString::Value(val as f64)
     }
 }
 
 impl From<i64> for FloatOrString {
     fn from(val: i64) -> FloatOrString {
-        FloatOrString(val as f64)
+        FloatOrString::Value(val as f64)
     }
 }
 
+/// Convert from number, with `NaN` as missing value
 impl From<f64> for FloatOrString {
     fn from(val: f64) -> FloatOrString {
-        FloatOrString(val)
+        if val.is_nan() {
+            FloatOrString::Missing
+        } else {
+            FloatOrString::Value(val)
+        }
     }
 }
 
 // Manual implementation of Display trait for nice printouts
 impl fmt::Display for FloatOrString {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}", self.0)
+        match self {
+            FloatOrString::Value(x) => write!(f, "{}", x),
+            FloatOrString::Percent(x) => write!(f, "{}%", x),
+            FloatOrString::Missing => write!(f, "n/a"),
+            FloatOrString::Text(note) => write!(f, "{}", note),
+        }
     }
 }
 
 /// Implement default instantiation
 impl default::Default for FloatOrString {
     fn default() -> FloatOrString {
-        FloatOrString(std::f64::NAN)
+        FloatOrString::Missing
     }
 }
 
@@ -158,9 +278,9 @@ mod tests {
         let json: serde_json::Value =
             serde_json::from_str("{\"x\":\"2.1\",\"y\":3,\"z\":3.4}").unwrap();
         let d_num: DoubleNum = serde_json::from_value(json).unwrap();
-        assert_eq!(d_num.x.0, 2.1);
-        assert_eq!(d_num.y.0, 3.0);
-        assert_eq!(d_num.z.0, 3.4);
+        assert_eq!(d_num.x.value(), 2.1);
+        assert_eq!(d_num.y.value(), 3.0);
+        assert_eq!(d_num.z.value(), 3.4);
     }
 
     #[test]
@@ -169,28 +289,89 @@ mod tests {
             serde_json::from_str("{\"x\":null, \"y\":1, \"z\":null}").unwrap();
         let d_num: DoubleNumOpt = serde_json::from_value(json).unwrap();
         assert!(d_num.x.is_none());
+
+        let json: serde_json::Value =
+            serde_json::from_str("{\"x\":null, \"y\":\"\", \"z\":0}").unwrap();
+        let d_num: DoubleNum = serde_json::from_value(json).unwrap();
+        assert!(d_num.x.is_missing());
+        assert!(d_num.y.is_missing());
+        assert_eq!(d_num.z.as_f64(), Some(0.0));
     }
 
     #[test]
     fn convert_vec_on_str_num() {
         let json: serde_json::Value = serde_json::from_str("[\"2.1\",3,3.4]").unwrap();
         let v: Vec<FloatOrString> = serde_json::from_value(json).unwrap();
-        assert_eq!(v[0].0, 2.1);
-        assert_eq!(v[1].0, 3.0);
-        assert_eq!(v[2].0, 3.4);
+        assert_eq!(v[0].value(), 2.1);
+        assert_eq!(v[1].value(), 3.0);
+        assert_eq!(v[2].value(), 3.4);
+    }
+
+    #[test]
+    fn convert_formatted_numbers() {
+        let parse = |s: &str| s.parse::<FloatOrString>().unwrap();
+        assert_eq!(parse("1,234.5"), FloatOrString::Value(1234.5));
+        assert_eq!(parse("$12.30"), FloatOrString::Value(12.3));
+        assert_eq!(parse("-$12.30"), FloatOrString::Value(-12.3));
+        assert_eq!(parse("(1,000)"), FloatOrString::Value(-1000.0));
+        assert_eq!(parse("12.5%"), FloatOrString::Percent(12.5));
+        assert_eq!(parse("(3.2%)"), FloatOrString::Percent(-3.2));
+        assert_eq!(parse("1.5K"), FloatOrString::Value(1500.0));
+        assert_eq!(parse("$2.3B"), FloatOrString::Value(2.3e9));
+        assert_eq!(parse("-4m"), FloatOrString::Value(-4e6));
+        assert_eq!(parse(" N/A "), FloatOrString::Missing);
+        let note = parse("Negative Tangible Equity");
+        assert_eq!(note.note(), Some("Negative Tangible Equity"));
+        assert!(note.value().is_nan());
+        assert_eq!(parse("inf").note(), Some("inf"));
+        assert_eq!(parse("B").note(), Some("B"));
+    }
+
+    #[test]
+    fn serialize_str_num() {
+        let values = vec![
+            FloatOrString::Value(2.5),
+            FloatOrString::Percent(-3.2),
+            FloatOrString::Missing,
+            FloatOrString::Text("Negative Tangible Equity".to_string()),
+        ];
+        let json = serde_json::to_value(&values).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!([2.5, "-3.2%", "", "Negative Tangible Equity"])
+        );
+        let copy: Vec<FloatOrString> = serde_json::from_value(json).unwrap();
+        assert_eq!(copy, values);
+    }
+
+    #[test]
+    fn serialize_nan_as_missing() {
+        let missing = FloatOrString::from(f64::NAN);
+        assert!(missing.is_missing());
+        let values = vec![
+            FloatOrString::Value(f64::NAN),
+            FloatOrString::Percent(f64::NAN),
+        ];
+        let json = serde_json::to_value(&values).unwrap();
+        assert_eq!(json, serde_json::json!(["", ""]));
+        let copy: Vec<FloatOrString> = serde_json::from_value(json).unwrap();
+        assert_eq!(copy, vec![missing.clone(), missing]);
     }
 
     #[test]
     fn print_str_num() {
-        let str_num = FloatOrString { 0: 2.3 };
+        let str_num = FloatOrString::Value(2.3);
         let num_as_str = format!("{}", str_num);
         assert_eq!(num_as_str, "2.3");
+        assert_eq!(FloatOrString::Percent(5.0).to_string(), "5%");
     }
 
     #[test]
     fn float_string_to_f64() {
-        let str_num = FloatOrString { 0: 2.3 };
+        let str_num = FloatOrString::Value(2.3);
         let num: f64 = str_num.into();
         assert_eq!(num, 2.3);
+        let num: f64 = FloatOrString::Missing.into();
+        assert!(num.is_nan());
     }
 }
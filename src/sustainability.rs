@@ -0,0 +1,425 @@
+//! Assessment of the sustainability of dividends, combining the dividend history with the
+//! income, cash flow and balance sheet statements of the annual financial data. Payout
+//! ratios are based on the company's total dividends paid according to the cash flow
+//! statement, which makes them independent of share counts and splits.
+
+use chrono::NaiveDate;
+use futures::future;
+
+use crate::corporate_actions::detect_corporate_actions;
+use crate::dates::FiscalPeriod;
+use crate::dividends::DividendHistory;
+use crate::financials::FinancialData;
+use crate::statements::{Series, Statements};
+use crate::symbol::Symbol;
+use crate::{GuruFocusConnector, GuruFocusError};
+
+/// Thresholds for the verdict on the sustainability of dividends
+#[derive(Debug, Clone, Copy)]
+pub struct SafetyThresholds {
+    /// Number of latest fiscal years used for the dividend coverage, the debt trend and
+    /// the search for dividend cuts
+    pub years: u32,
+    /// Payout ratio on earnings above which the verdict is at least yellow
+    pub payout_warning: f64,
+    /// Payout ratio on earnings above which the verdict is red
+    pub payout_limit: f64,
+    /// Payout ratio on free cash flow above which the verdict is at least yellow
+    pub fcf_payout_warning: f64,
+    /// Payout ratio on free cash flow above which the verdict is red
+    pub fcf_payout_limit: f64,
+    /// Dividend coverage below which the verdict is red
+    pub min_coverage: f64,
+    /// Annual growth rate of long-term debt above which the verdict is at least yellow
+    pub debt_growth_warning: f64,
+}
+
+impl Default for SafetyThresholds {
+    fn default() -> SafetyThresholds {
+        SafetyThresholds {
+            years: 5,
+            payout_warning: 0.6,
+            payout_limit: 0.9,
+            fcf_payout_warning: 0.7,
+            fcf_payout_limit: 1.0,
+            min_coverage: 1.0,
+            debt_growth_warning: 0.1,
+        }
+    }
+}
+
+/// Traffic-light verdict on the sustainability of dividends
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Verdict {
+    /// Dividends well covered by earnings and free cash flow
+    Green,
+    /// Dividends covered, but with high payout ratios, rising debt or recent cuts
+    Yellow,
+    /// Dividends not covered by earnings or free cash flow, or suspended
+    Red,
+}
+
+/// Report on the sustainability of the dividends of a stock
+#[derive(Debug, Clone, PartialEq)]
+pub struct DividendSafety {
+    pub symbol: Symbol,
+    /// Fiscal period of the payout ratios, i.e. the latest period with dividends paid,
+    /// preferably the trailing twelve months
+    pub period: FiscalPeriod,
+    /// Dividends paid divided by net income, if earnings are positive
+    pub payout_ratio: Option<f64>,
+    /// Dividends paid divided by free cash flow, if free cash flow is positive
+    pub fcf_payout_ratio: Option<f64>,
+    /// Sum of free cash flow divided by sum of dividends paid over the latest fiscal years
+    pub coverage: Option<f64>,
+    /// Annual growth rate of long-term debt over the latest fiscal years
+    pub debt_trend: Option<f64>,
+    /// Consecutive calendar years of dividend growth, see `DividendHistory::growth_streak`
+    pub growth_streak: u32,
+    /// Number of dividend cuts within the latest years
+    pub recent_cuts: usize,
+    /// Verdict on the dividends, or `None` if no dividends were paid in any period
+    pub verdict: Option<Verdict>,
+    /// Findings leading to a verdict other than green, or the note that no dividends
+    /// were paid
+    pub reasons: Vec<String>,
+}
+
+impl DividendSafety {
+    /// Assess the dividends of a stock as of the given date based on its financial data and
+    /// dividend history; amounts of the history should be adjusted for splits (see
+    /// `DividendHistory::split_adjusted`), else splits are reported as cuts
+    pub fn assess(
+        symbol: impl Into<Symbol>,
+        financials: &FinancialData,
+        dividends: &DividendHistory,
+        as_of: NaiveDate,
+        thresholds: &SafetyThresholds,
+    ) -> Result<DividendSafety, GuruFocusError> {
+        let statements = financials.financials.annual_statements()?;
+        let net_income = statements.income_statement.net_income();
+        let fcf = statements.cashflow_statement.free_cash_flow();
+        let paid = statements.cashflow_statement.dividends();
+
+        let columns = statements.periods.len();
+        let period_of = |idx: usize| {
+            statements
+                .periods
+                .get(idx)
+                .copied()
+                .unwrap_or(FiscalPeriod::Ttm)
+        };
+        let paid_in = |idx: usize| value(paid, idx).map(f64::abs).filter(|paid| *paid > 0.0);
+
+        // payout ratios of the latest column with dividends paid, i.e. preferably TTM
+        let last_paid = (0..columns).rev().find(|&idx| paid_in(idx).is_some());
+        let latest = last_paid.unwrap_or_else(|| columns.saturating_sub(1));
+        let period = period_of(latest);
+        let paid_latest = paid_in(latest).unwrap_or(0.0);
+        // a later column reporting zero dividends means the dividend was suspended
+        let suspended = last_paid.and_then(|last_paid| {
+            (last_paid + 1..columns)
+                .rev()
+                .find(|&idx| value(paid, idx).is_some())
+        });
+        let payout_ratio = ratio(paid_latest, value(net_income, latest));
+        let fcf_payout_ratio = ratio(paid_latest, value(fcf, latest));
+
+        let window = fiscal_years(&statements, thresholds.years);
+        let fcf_sum: Option<f64> = window.iter().map(|&idx| value(fcf, idx)).sum();
+        let paid_sum: f64 = window
+            .iter()
+            .filter_map(|&idx| value(paid, idx))
+            .map(f64::abs)
+            .sum();
+        let coverage = match fcf_sum {
+            Some(fcf_sum) if paid_sum > 0.0 => Some(fcf_sum / paid_sum),
+            _ => None,
+        };
+        let debt_trend = debt_trend(&statements, &window);
+
+        let cuts_since = window
+            .first()
+            .and_then(|&idx| statements.periods[idx].end_date())
+            .unwrap_or(as_of);
+        let recent_cuts = dividends
+            .cuts()
+            .iter()
+            .filter(|cut| cut.ex_date > cuts_since && cut.ex_date <= as_of)
+            .count();
+
+        let mut safety = DividendSafety {
+            symbol: symbol.into(),
+            period,
+            payout_ratio,
+            fcf_payout_ratio,
+            coverage,
+            debt_trend,
+            growth_streak: dividends.growth_streak(as_of),
+            recent_cuts,
+            verdict: None,
+            reasons: Vec::new(),
+        };
+        // there is nothing to sustain for companies not paying dividends
+        if paid_latest <= 0.0 {
+            safety
+                .reasons
+                .push(format!("no dividends paid in {}", period));
+            return Ok(safety);
+        }
+        safety.verdict = Some(Verdict::Green);
+        if let Some(idx) = suspended {
+            safety.flag(
+                Verdict::Red,
+                format!(
+                    "dividends suspended in {}, last paid in {}",
+                    period_of(idx),
+                    period
+                ),
+            );
+        }
+        safety.check_payout(
+            "payout ratio on earnings",
+            payout_ratio,
+            thresholds.payout_warning,
+            thresholds.payout_limit,
+        );
+        safety.check_payout(
+            "payout ratio on free cash flow",
+            fcf_payout_ratio,
+            thresholds.fcf_payout_warning,
+            thresholds.fcf_payout_limit,
+        );
+        if let Some(coverage) = coverage.filter(|coverage| *coverage < thresholds.min_coverage) {
+            safety.flag(
+                Verdict::Red,
+                format!(
+                    "dividend coverage of {:.2} below {:.2}",
+                    coverage, thresholds.min_coverage
+                ),
+            );
+        }
+        if let Some(trend) = debt_trend.filter(|trend| *trend > thresholds.debt_growth_warning) {
+            safety.flag(
+                Verdict::Yellow,
+                format!("long-term debt growing by {:.1}% per year", trend * 100.0),
+            );
+        }
+        if recent_cuts > 0 {
+            safety.flag(
+                Verdict::Yellow,
+                format!("{} dividend cut(s) since {}", recent_cuts, cuts_since),
+            );
+        }
+        Ok(safety)
+    }
+
+    fn check_payout(&mut self, name: &str, payout: Option<f64>, warning: f64, limit: f64) {
+        match payout {
+            None => self.flag(Verdict::Red, format!("{} not covered", name)),
+            Some(payout) if payout > limit => self.flag(
+                Verdict::Red,
+                format!(
+                    "{} of {:.0}% above {:.0}%",
+                    name,
+                    payout * 100.0,
+                    limit * 100.0
+                ),
+            ),
+            Some(payout) if payout > warning => self.flag(
+                Verdict::Yellow,
+                format!(
+                    "{} of {:.0}% above {:.0}%",
+                    name,
+                    payout * 100.0,
+                    warning * 100.0
+                ),
+            ),
+            Some(_) => {}
+        }
+    }
+
+    fn flag(&mut self, verdict: Verdict, reason: String) {
+        self.verdict = self.verdict.max(Some(verdict));
+        self.reasons.push(reason);
+    }
+}
+
+fn value(series: &Series, idx: usize) -> Option<f64> {
+    series.get(idx).and_then(|value| value.as_f64())
+}
+
+/// Dividends paid relative to a positive base, e.g. net income
+fn ratio(paid: f64, base: Option<f64>) -> Option<f64> {
+    base.filter(|base| *base > 0.0).map(|base| paid / base)
+}
+
+/// Indices of the given number of latest fiscal years, excluding the TTM column
+fn fiscal_years(statements: &Statements, years: u32) -> Vec<usize> {
+    let mut indices: Vec<usize> = (0..statements.periods.len())
+        .filter(|&idx| !statements.periods[idx].is_ttm())
+        .collect();
+    let skip = indices.len().saturating_sub(years as usize);
+    indices.drain(..skip);
+    indices
+}
+
+/// Annual growth rate of long-term debt from the first to the last of the given fiscal years
+fn debt_trend(statements: &Statements, window: &[usize]) -> Option<f64> {
+    let debt = statements.balance_sheet.long_term_debt();
+    let (&first, &last) = (window.first()?, window.last()?);
+    let start = value(debt, first).filter(|start| *start > 0.0)?;
+    let end = value(debt, last)?;
+    let years = (last - first) as f64;
+    if years > 0.0 && end >= 0.0 {
+        Some((end / start).powf(1.0 / years) - 1.0)
+    } else {
+        None
+    }
+}
+
+impl GuruFocusConnector {
+    /// Returns the dividend sustainability reports of the given symbols as of the given date.
+    /// Requires four requests per symbol (financials, dividend history and both price
+    /// histories, which are used to adjust the dividend history for splits); the symbols
+    /// are processed concurrently.
+    pub async fn dividend_safety(
+        &self,
+        stocks: &[impl Into<Symbol> + Clone],
+        as_of: NaiveDate,
+        thresholds: &SafetyThresholds,
+    ) -> Result<Vec<DividendSafety>, GuruFocusError> {
+        let symbols: Vec<Symbol> = stocks.iter().cloned().map(Into::into).collect();
+        future::try_join_all(symbols.into_iter().map(|symbol| async move {
+            let (financials, dividends, adjusted, unadjusted) = future::try_join4(
+                self.financials(&symbol),
+                self.dividend_history(&symbol),
+                self.price_hist(&symbol),
+                self.unadj_price_hist(&symbol),
+            )
+            .await?;
+            let actions = detect_corporate_actions(&adjusted, &unadjusted, &dividends);
+            let dividends = DividendHistory::new(dividends).split_adjusted(&actions);
+            DividendSafety::assess(symbol, &financials, &dividends, as_of, thresholds)
+        }))
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::replay::fixture_connector;
+    use crate::test_util::{assert_close, ymd};
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn safety_from_fixture() {
+        let gf_connect = fixture_connector();
+        let financials = gf_connect.financials("NAS:MSFT").await.unwrap();
+        let dividends =
+            DividendHistory::new(gf_connect.dividend_history("NAS:MSFT").await.unwrap());
+        let as_of = ymd(2024, 1, 1);
+        let thresholds = SafetyThresholds::default();
+        let safety =
+            DividendSafety::assess("NAS:MSFT", &financials, &dividends, as_of, &thresholds)
+                .unwrap();
+        assert_eq!(safety.symbol, "NAS:MSFT");
+        assert_eq!(safety.period, FiscalPeriod::Ttm);
+        assert_close(safety.payout_ratio.unwrap(), 21040.0 / 82541.0, 1e-9);
+        assert_close(safety.fcf_payout_ratio.unwrap(), 21040.0 / 62079.0, 1e-9);
+        assert_close(safety.coverage.unwrap(), 264236.0 / 83404.0, 1e-9);
+        assert_close(
+            safety.debt_trend.unwrap(),
+            (41990.0f64 / 66662.0).powf(0.25) - 1.0,
+            1e-9,
+        );
+        assert_eq!(safety.growth_streak, 9);
+        assert_eq!(safety.recent_cuts, 0);
+        assert_eq!(safety.verdict, Some(Verdict::Green));
+        assert!(safety.reasons.is_empty());
+
+        let strict = SafetyThresholds {
+            payout_warning: 0.2,
+            fcf_payout_limit: 0.3,
+            ..thresholds
+        };
+        let safety =
+            DividendSafety::assess("NAS:MSFT", &financials, &dividends, as_of, &strict).unwrap();
+        assert_eq!(safety.verdict, Some(Verdict::Red));
+        assert_eq!(
+            safety.reasons,
+            vec![
+                "payout ratio on earnings of 25% above 20%",
+                "payout ratio on free cash flow of 34% above 30%"
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn no_dividends_paid() {
+        let gf_connect = fixture_connector();
+        let mut financials = gf_connect.financials("NAS:MSFT").await.unwrap();
+        let dividends = DividendHistory::default();
+        let as_of = ymd(2024, 1, 1);
+        let thresholds = SafetyThresholds::default();
+
+        let cash_flow = &mut financials.financials.annuals.cashflow_statement;
+        cash_flow["Cash Flow for Dividends"] = json!(["0", "0", "0", "0", "0", "0"]);
+        let safety =
+            DividendSafety::assess("NAS:MSFT", &financials, &dividends, as_of, &thresholds)
+                .unwrap();
+        assert_eq!(safety.verdict, None);
+        assert_eq!(safety.payout_ratio, Some(0.0));
+        assert_eq!(safety.coverage, None);
+        assert_eq!(safety.reasons, vec!["no dividends paid in TTM"]);
+
+        let cash_flow = &mut financials.financials.annuals.cashflow_statement;
+        cash_flow
+            .as_object_mut()
+            .unwrap()
+            .remove("Cash Flow for Dividends");
+        let safety =
+            DividendSafety::assess("NAS:MSFT", &financials, &dividends, as_of, &thresholds)
+                .unwrap();
+        assert_eq!(safety.verdict, None);
+        assert_eq!(safety.period, FiscalPeriod::Ttm);
+        assert_eq!(safety.reasons, vec!["no dividends paid in TTM"]);
+    }
+
+    #[tokio::test]
+    async fn suspended_dividends() {
+        let gf_connect = fixture_connector();
+        let mut financials = gf_connect.financials("NAS:MSFT").await.unwrap();
+        let dividends = DividendHistory::default();
+        let as_of = ymd(2024, 1, 1);
+        let thresholds = SafetyThresholds::default();
+
+        let cash_flow = &mut financials.financials.annuals.cashflow_statement;
+        cash_flow["Cash Flow for Dividends"][5] = json!("0");
+        let safety =
+            DividendSafety::assess("NAS:MSFT", &financials, &dividends, as_of, &thresholds)
+                .unwrap();
+        assert_eq!(safety.verdict, Some(Verdict::Red));
+        assert_eq!(safety.period, "2023-06".parse().unwrap());
+        assert_close(safety.payout_ratio.unwrap(), 19800.0 / 72361.0, 1e-9);
+        assert_eq!(
+            safety.reasons,
+            vec!["dividends suspended in TTM, last paid in 2023-06"]
+        );
+
+        // suspended in the latest fiscal year, TTM not reported
+        let cash_flow = &mut financials.financials.annuals.cashflow_statement;
+        cash_flow["Cash Flow for Dividends"][4] = json!("0");
+        cash_flow["Cash Flow for Dividends"][5] = json!("");
+        let safety =
+            DividendSafety::assess("NAS:MSFT", &financials, &dividends, as_of, &thresholds)
+                .unwrap();
+        assert_eq!(safety.verdict, Some(Verdict::Red));
+        assert_eq!(safety.period, "2022-06".parse().unwrap());
+        assert_eq!(
+            safety.reasons,
+            vec!["dividends suspended in 2023-06, last paid in 2022-06"]
+        );
+    }
+}
@@ -0,0 +1,296 @@
+//! Stock symbols consisting of an optional exchange prefix and a ticker, e.g. `NAS:AAPL`,
+//! `FRA:APC` or just `AMZN`. Symbols without exchange refer to the US listing, which is
+//! the default of the GuruFocus API.
+//!
+//! Tickers are normalized to upper case, and share classes are always separated by a dot
+//! (`BRK.A`), regardless of whether they are given as `BRK-A`, `BRK/A` or `brk.a`.
+
+use serde::de::{self, Deserializer, Visitor};
+use serde::{Deserialize, Serialize, Serializer};
+use std::fmt;
+use std::str::FromStr;
+
+use crate::GuruFocusError;
+
+/// Exchanges of the US market, which is assumed if no exchange is given
+pub const US_EXCHANGES: [&str; 6] = ["NAS", "NYSE", "AMEX", "ARCA", "BATS", "OTCPK"];
+
+/// Stock symbol with optional exchange
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Symbol {
+    exchange: Option<String>,
+    ticker: String,
+}
+
+impl Symbol {
+    /// Create symbol from ticker and optional exchange, normalizing both
+    pub fn new(exchange: Option<&str>, ticker: &str) -> Symbol {
+        Symbol {
+            exchange: exchange
+                .map(|e| e.trim().to_uppercase())
+                .filter(|e| !e.is_empty()),
+            ticker: normalize_ticker(ticker),
+        }
+    }
+
+    /// Returns the ticker without exchange, e.g. `BRK.A`
+    pub fn ticker(&self) -> &str {
+        &self.ticker
+    }
+
+    /// Returns the exchange, if given explicitly
+    pub fn exchange(&self) -> Option<&str> {
+        self.exchange.as_deref()
+    }
+
+    /// Returns the share class, e.g. `A` for `BRK.A`
+    pub fn share_class(&self) -> Option<&str> {
+        self.ticker
+            .rsplit_once('.')
+            .map(|(_, class)| class)
+            .filter(|class| is_share_class(class))
+    }
+
+    /// Returns true for symbols listed at a US exchange or without exchange
+    pub fn is_us(&self) -> bool {
+        match &self.exchange {
+            None => true,
+            Some(exchange) => US_EXCHANGES.contains(&exchange.as_str()),
+        }
+    }
+
+    /// Returns the same ticker at the given exchange
+    pub fn with_exchange(&self, exchange: &str) -> Symbol {
+        Symbol::new(Some(exchange), &self.ticker)
+    }
+
+    /// Returns the symbol if it can be used in a request, or else an `InvalidSymbol` error
+    pub(crate) fn validated(self) -> Result<Symbol, GuruFocusError> {
+        match self.validate() {
+            Ok(()) => Ok(self),
+            Err(reason) => Err(GuruFocusError::InvalidSymbol {
+                symbol: self.to_string(),
+                reason,
+            }),
+        }
+    }
+
+    /// Check that the symbol can be used in a request
+    fn validate(&self) -> Result<(), &'static str> {
+        let is_valid = |c: char| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '&' | '^');
+        if self.ticker.is_empty() {
+            Err("empty ticker")
+        } else if !self.ticker.chars().all(is_valid) {
+            Err("invalid character in ticker")
+        } else if let Some(exchange) = &self.exchange {
+            if exchange.chars().all(|c| c.is_ascii_alphanumeric()) {
+                Ok(())
+            } else {
+                Err("invalid character in exchange")
+            }
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Data structs holding a symbol without exchange together with the exchange it is
+/// listed on, e.g. quotes or guru picks
+pub trait HasExchange {
+    /// Returns the symbol as delivered by GuruFocus
+    fn symbol(&self) -> &Symbol;
+
+    /// Returns the exchange the symbol is listed on, e.g. `NAS`
+    fn exchange(&self) -> &str;
+
+    /// Returns the symbol including the exchange, e.g. `NAS:AAPL`
+    fn qualified_symbol(&self) -> Symbol {
+        self.symbol().with_exchange(self.exchange())
+    }
+}
+
+macro_rules! impl_has_exchange {
+    ($($type:ty),*) => {
+        $(
+            impl HasExchange for $type {
+                fn symbol(&self) -> &Symbol {
+                    &self.symbol
+                }
+
+                fn exchange(&self) -> &str {
+                    &self.exchange
+                }
+            }
+        )*
+    };
+}
+
+impl_has_exchange!(
+    crate::stock::Stock,
+    crate::stock::Quote,
+    crate::stock::Country,
+    crate::gurus::GuruPick,
+    crate::gurus::GuruPosition,
+    crate::gurus::PoliticianTransaction,
+    crate::insiders::InsiderUpdate
+);
+
+/// Share classes are single letters, e.g. `A` in `BRK.A`
+fn is_share_class(class: &str) -> bool {
+    class.len() == 1 && class.chars().all(|c| c.is_ascii_alphabetic())
+}
+
+fn normalize_ticker(ticker: &str) -> String {
+    let ticker = ticker.trim().to_uppercase();
+    match ticker.rfind(['-', '/']) {
+        Some(idx) if idx > 0 && is_share_class(&ticker[idx + 1..]) => {
+            format!("{}.{}", &ticker[..idx], &ticker[idx + 1..])
+        }
+        _ => ticker,
+    }
+}
+
+impl fmt::Display for Symbol {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.exchange {
+            Some(exchange) => write!(f, "{}:{}", exchange, self.ticker),
+            None => write!(f, "{}", self.ticker),
+        }
+    }
+}
+
+/// Parse and validate symbol given as `EXCHANGE:TICKER` or `TICKER`
+impl FromStr for Symbol {
+    type Err = GuruFocusError;
+
+    fn from_str(s: &str) -> Result<Symbol, GuruFocusError> {
+        let symbol = Symbol::from(s);
+        symbol
+            .validate()
+            .map_err(|reason| GuruFocusError::InvalidSymbol {
+                symbol: s.to_string(),
+                reason,
+            })?;
+        Ok(symbol)
+    }
+}
+
+/// Normalize symbol without validation, e.g. to pass symbols from user input or
+/// responses of GuruFocus as they are; the connector validates symbols before
+/// sending a request
+impl From<&str> for Symbol {
+    fn from(s: &str) -> Symbol {
+        match s.split_once(':') {
+            Some((exchange, ticker)) => Symbol::new(Some(exchange), ticker),
+            None => Symbol::new(None, s),
+        }
+    }
+}
+
+impl From<String> for Symbol {
+    fn from(s: String) -> Symbol {
+        Symbol::from(s.as_str())
+    }
+}
+
+impl From<&String> for Symbol {
+    fn from(s: &String) -> Symbol {
+        Symbol::from(s.as_str())
+    }
+}
+
+impl From<&Symbol> for Symbol {
+    fn from(symbol: &Symbol) -> Symbol {
+        symbol.clone()
+    }
+}
+
+impl PartialEq<str> for Symbol {
+    fn eq(&self, other: &str) -> bool {
+        let other = Symbol::from(other);
+        *self == other
+    }
+}
+
+impl PartialEq<&str> for Symbol {
+    fn eq(&self, other: &&str) -> bool {
+        let other = Symbol::from(*other);
+        *self == other
+    }
+}
+
+impl Serialize for Symbol {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Symbol {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Symbol, D::Error> {
+        struct SymbolVisitor;
+
+        impl<'de> Visitor<'de> for SymbolVisitor {
+            type Value = Symbol;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("stock symbol")
+            }
+
+            fn visit_str<E: de::Error>(self, value: &str) -> Result<Symbol, E> {
+                Ok(Symbol::from(value))
+            }
+
+            // some tickers, e.g. at the Hong Kong exchange, are numbers
+            fn visit_u64<E: de::Error>(self, value: u64) -> Result<Symbol, E> {
+                Ok(Symbol::from(value.to_string()))
+            }
+        }
+
+        deserializer.deserialize_any(SymbolVisitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_symbols() {
+        let symbol: Symbol = "nas:aapl".parse().unwrap();
+        assert_eq!(symbol.exchange(), Some("NAS"));
+        assert_eq!(symbol.ticker(), "AAPL");
+        assert_eq!(symbol.to_string(), "NAS:AAPL");
+        assert!(symbol.is_us());
+
+        let symbol: Symbol = "AMZN".parse().unwrap();
+        assert_eq!(symbol.exchange(), None);
+        assert!(symbol.is_us());
+        assert!(!Symbol::from("FRA:APC").is_us());
+
+        assert!("".parse::<Symbol>().is_err());
+        assert!("NAS:".parse::<Symbol>().is_err());
+        assert!("NAS:AAPL,MSFT".parse::<Symbol>().is_err());
+        assert!("N/A:X".parse::<Symbol>().is_err());
+    }
+
+    #[test]
+    fn share_classes() {
+        for s in ["NYSE:BRK.A", "NYSE:BRK-A", "nyse:brk/a", " NYSE:BRK.A "] {
+            let symbol = Symbol::from(s);
+            assert_eq!(symbol.to_string(), "NYSE:BRK.A");
+            assert_eq!(symbol.share_class(), Some("A"));
+        }
+        assert_eq!(Symbol::from("BAC-PL").ticker(), "BAC-PL");
+        assert_eq!(Symbol::from("BAC-PL").share_class(), None);
+        assert_eq!(Symbol::from("MSFT"), "msft");
+    }
+
+    #[test]
+    fn serde_round_trip() {
+        let symbol: Symbol = serde_json::from_str(r#""xter:sap""#).unwrap();
+        assert_eq!(symbol, Symbol::new(Some("XTER"), "SAP"));
+        assert_eq!(serde_json::to_string(&symbol).unwrap(), r#""XTER:SAP""#);
+        let symbol: Symbol = serde_json::from_str("700").unwrap();
+        assert_eq!(symbol.ticker(), "700");
+    }
+}
@@ -0,0 +1,200 @@
+//! Total return of a stock with all dividends reinvested. The index is built from the
+//! unadjusted price history and the dividend history: each dividend is converted into
+//! the currency of the prices and reinvested at the first closing price on or after its
+//! ex-date, and splits (e.g. as inferred by `detect_corporate_actions`) multiply the
+//! number of shares held.
+
+use chrono::NaiveDate;
+use std::collections::HashMap;
+
+use crate::corporate_actions::CorporateAction;
+use crate::prices::PriceSeries;
+use crate::stock::Dividend;
+use crate::GuruFocusError;
+
+/// Value of the total return index at the first price
+const INDEX_START: f64 = 100.0;
+
+/// Options for building the total return index
+#[derive(Debug, Clone)]
+pub struct TotalReturnOptions {
+    /// Currency of the prices, e.g. `Quote::currency`; dividends paid in other currencies
+    /// are converted using `exchange_rates`
+    pub price_currency: String,
+    /// Whether dividends marked as special in `Dividend::div_type` are reinvested like
+    /// regular ones, or ignored, e.g. if they are treated as return of capital
+    pub include_special_dividends: bool,
+    /// Exchange rates per dividend currency, given as price of one unit of the dividend
+    /// currency in the price currency; the latest rate at or before the ex-date is used
+    pub exchange_rates: HashMap<String, PriceSeries>,
+}
+
+impl TotalReturnOptions {
+    /// Options for prices in the given currency, including special dividends
+    pub fn new(price_currency: &str) -> TotalReturnOptions {
+        TotalReturnOptions {
+            price_currency: price_currency.to_string(),
+            include_special_dividends: true,
+            exchange_rates: HashMap::new(),
+        }
+    }
+
+    /// Add exchange rates of the given dividend currency
+    pub fn exchange_rates(mut self, currency: &str, rates: PriceSeries) -> TotalReturnOptions {
+        self.exchange_rates.insert(currency.to_string(), rates);
+        self
+    }
+
+    /// Convert the dividend into the price currency
+    fn convert(&self, dividend: &Dividend, amount: f64) -> Result<f64, GuruFocusError> {
+        if dividend.currency.is_empty() || dividend.currency == self.price_currency {
+            return Ok(amount);
+        }
+        self.exchange_rates
+            .get(&dividend.currency)
+            .and_then(|rates| rates.at_or_before(dividend.ex_date))
+            .map(|(_, rate)| amount * rate)
+            .ok_or_else(|| GuruFocusError::MissingExchangeRate {
+                from: dividend.currency.clone(),
+                to: self.price_currency.clone(),
+                date: dividend.ex_date,
+            })
+    }
+}
+
+/// Build the total return index starting at 100 from unadjusted prices, the dividend
+/// history and the splits contained in `actions`; all other actions are ignored.
+/// Dividends without numeric amount and dividends outside the range of the prices are
+/// skipped.
+pub fn total_return_index(
+    unadjusted: &PriceSeries,
+    dividends: &[Dividend],
+    actions: &[CorporateAction],
+    options: &TotalReturnOptions,
+) -> Result<PriceSeries, GuruFocusError> {
+    let (start_date, start_price) = match unadjusted.first() {
+        Some(first) => first,
+        None => return Ok(PriceSeries::default()),
+    };
+    let mut dividends: Vec<&Dividend> = dividends
+        .iter()
+        .filter(|dividend| options.include_special_dividends || !dividend.is_special())
+        .filter(|dividend| dividend.ex_date > start_date)
+        .collect();
+    dividends.sort_by_key(|dividend| dividend.ex_date);
+    let mut dividends = dividends.into_iter().peekable();
+
+    let mut shares = INDEX_START / start_price;
+    let mut previous = start_date;
+    let mut index = Vec::with_capacity(unadjusted.len());
+    for &(date, price) in unadjusted.iter() {
+        // splits are effective before the first trading day at or after their date
+        for action in actions {
+            if let CorporateAction::Split {
+                date: split_date,
+                ratio,
+            } = action
+            {
+                if *split_date > previous && *split_date <= date {
+                    shares *= ratio;
+                }
+            }
+        }
+        while let Some(dividend) = dividends.next_if(|dividend| dividend.ex_date <= date) {
+            if let Some(amount) = dividend.amount.as_f64() {
+                shares += shares * options.convert(dividend, amount)? / price;
+            }
+        }
+        index.push((date, shares * price));
+        previous = date;
+    }
+    Ok(PriceSeries::new(index))
+}
+
+/// Returns the annualized return of a price or total return index between the latest
+/// values at or before the given dates
+pub fn annualized_return(index: &PriceSeries, start: NaiveDate, end: NaiveDate) -> Option<f64> {
+    let start = index.at_or_before(start)?;
+    let end = index.at_or_before(end)?;
+    PriceSeries::new(vec![start, end]).cagr()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::corporate_actions::detect_corporate_actions;
+    use crate::replay::fixture_connector;
+    use crate::test_util::{assert_close, ymd};
+    use serde_json::json;
+
+    fn dividend(ex_date: &str, amount: &str, currency: &str, div_type: &str) -> Dividend {
+        serde_json::from_value(json!({
+            "ex_date": ex_date, "record_date": "", "amount": amount, "pay_date": "",
+            "currency": currency, "type": div_type
+        }))
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn total_return_from_fixture() {
+        let gf_connect = fixture_connector();
+        let adjusted = gf_connect.price_hist("NAS:AAPL").await.unwrap();
+        let unadjusted = gf_connect.unadj_price_hist("NAS:AAPL").await.unwrap();
+        let dividends = gf_connect.dividend_history("NAS:AAPL").await.unwrap();
+        let actions = detect_corporate_actions(&adjusted, &unadjusted, &dividends);
+        let options = TotalReturnOptions::new("USD");
+        let index = total_return_index(&unadjusted, &dividends, &actions, &options).unwrap();
+        assert_eq!(index.len(), unadjusted.len());
+        let (start, first) = index.first().unwrap();
+        assert_eq!(start, ymd(2020, 7, 27));
+        assert_close(first, 100.0, 1e-9);
+        let expected = 100.0 * (1.0 + 0.82 / 444.45) * 4.0 * 112.0 / 373.85;
+        let (_, last) = index.last().unwrap();
+        assert_close(last, expected, 1e-9);
+        // close to the adjusted prices, which reinvest at the close before the ex-date
+        let (_, first_adjusted) = adjusted.first().unwrap();
+        assert_close(last / 100.0, 112.0 / first_adjusted, 1e-4);
+
+        let start = ymd(2020, 8, 1);
+        let end = ymd(2020, 8, 30);
+        let total = annualized_return(&index, start, end).unwrap();
+        let price_only = annualized_return(&unadjusted.range(..ymd(2020, 8, 31)), start, end);
+        assert!(total > price_only.unwrap());
+        assert_eq!(annualized_return(&index, ymd(2020, 1, 1), end), None);
+    }
+
+    #[test]
+    fn currencies_and_special_dividends() {
+        let prices: PriceSeries = vec![
+            (ymd(2023, 1, 2), 50.0),
+            (ymd(2023, 1, 3), 50.0),
+            (ymd(2023, 1, 6), 40.0),
+        ]
+        .into_iter()
+        .collect();
+        let dividends = vec![
+            dividend("2023-01-03", "1.0", "USD", "Cash Div."),
+            dividend("2023-01-05", "10", "EUR", "Special Div."),
+        ];
+        let rates: PriceSeries = vec![(ymd(2022, 12, 30), 0.9)].into_iter().collect();
+
+        let options = TotalReturnOptions::new("EUR");
+        let err = total_return_index(&prices, &dividends, &[], &options).unwrap_err();
+        assert!(matches!(err, GuruFocusError::MissingExchangeRate { .. }));
+
+        let options = options.exchange_rates("USD", rates);
+        let index = total_return_index(&prices, &dividends, &[], &options).unwrap();
+        let shares = 2.0 * (1.0 + 0.9 / 50.0);
+        assert_close(index.points()[1].1, shares * 50.0, 1e-9);
+        // special dividend reinvested at the next trading day
+        let shares = shares * (1.0 + 10.0 / 40.0);
+        assert_close(index.points()[2].1, shares * 40.0, 1e-9);
+
+        let options = TotalReturnOptions {
+            include_special_dividends: false,
+            ..options
+        };
+        let index = total_return_index(&prices, &dividends, &[], &options).unwrap();
+        assert_close(index.points()[2].1, 2.0 * (1.0 + 0.9 / 50.0) * 40.0, 1e-9);
+    }
+}
@@ -0,0 +1,483 @@
+//! Intrinsic value calculators reproducing the valuation lines of `Chart`. The inputs are
+//! collected from the financial data (using TTM figures where available), the key ratios
+//! and the analyst estimates into `ValuationInputs`, which may be modified to run
+//! scenarios; the models themselves are parameterized by `ValuationAssumptions`.
+//!
+//! Graham Number, Net Current Asset Value and Tangible Book follow the published formulas
+//! and match the chart. The other lines depend on growth rates and averages GuruFocus does
+//! not publish, so the values computed here deviate from the chart unless the assumptions
+//! are tuned accordingly.
+
+use serde::{Deserialize, Serialize};
+
+use crate::financials::{DataPeriods, FinancialData};
+use crate::keyratios::{AnalystEstimates, KeyRatios};
+use crate::series::FinancialSeries;
+use crate::stock::Chart;
+use crate::symbol::Symbol;
+use crate::{GuruFocusConnector, GuruFocusError};
+
+/// Smallest growth rate used for the Peter Lynch Value
+const LYNCH_MIN_GROWTH: f64 = 0.05;
+/// Largest growth rate used for the Peter Lynch Value
+const LYNCH_MAX_GROWTH: f64 = 0.25;
+
+/// Assumptions of the valuation models
+#[derive(Debug, Clone, Copy)]
+pub struct ValuationAssumptions {
+    /// Annual discount rate of the DCF models and capitalization rate of the Earnings
+    /// Power Value
+    pub discount_rate: f64,
+    /// Growth rate of earnings during the growth stage; if `None`, the growth rate of
+    /// the inputs is used
+    pub earnings_growth: Option<f64>,
+    /// Growth rate of free cash flow during the growth stage; if `None`, the growth rate
+    /// of the inputs is used
+    pub fcf_growth: Option<f64>,
+    /// Number of years of the growth stage
+    pub growth_years: u32,
+    /// Growth rate during the terminal stage
+    pub terminal_growth: f64,
+    /// Number of years of the terminal stage
+    pub terminal_years: u32,
+    /// Lower bound of the growth rates during the growth stage
+    pub min_growth: f64,
+    /// Upper bound of the growth rates during the growth stage
+    pub max_growth: f64,
+    /// Whether the tangible book value is added to the DCF values
+    pub add_tangible_book: bool,
+    /// Multiplier of the Graham Number, i.e. maximum P/E times maximum P/B
+    pub graham_multiplier: f64,
+}
+
+impl Default for ValuationAssumptions {
+    fn default() -> ValuationAssumptions {
+        ValuationAssumptions {
+            discount_rate: 0.11,
+            earnings_growth: None,
+            fcf_growth: None,
+            growth_years: 10,
+            terminal_growth: 0.04,
+            terminal_years: 10,
+            min_growth: 0.05,
+            max_growth: 0.2,
+            add_tangible_book: true,
+            graham_multiplier: 22.5,
+        }
+    }
+}
+
+impl ValuationAssumptions {
+    /// Returns the value of a per share amount (e.g. EPS) growing by `growth` during the
+    /// growth stage and by `terminal_growth` during the terminal stage, discounted by
+    /// `discount_rate`; `growth` is not bounded by `min_growth` and `max_growth`
+    pub fn discounted_value(&self, amount: f64, growth: f64) -> f64 {
+        let growth_factor = (1.0 + growth) / (1.0 + self.discount_rate);
+        let terminal_factor = (1.0 + self.terminal_growth) / (1.0 + self.discount_rate);
+        let growth_stage = geometric_sum(growth_factor, self.growth_years);
+        let terminal_stage = growth_factor.powi(self.growth_years as i32)
+            * geometric_sum(terminal_factor, self.terminal_years);
+        amount * (growth_stage + terminal_stage)
+    }
+
+    fn bounded(&self, growth: f64) -> f64 {
+        growth.max(self.min_growth).min(self.max_growth)
+    }
+}
+
+/// Sum of `factor^t` for `t` from 1 to `years`
+fn geometric_sum(factor: f64, years: u32) -> f64 {
+    (1..=years as i32).map(|t| factor.powi(t)).sum()
+}
+
+/// Inputs of the valuation models; per share values refer to the trailing twelve months
+/// where available, otherwise to the latest fiscal year, and rates are given as fraction,
+/// e.g. 0.15 for 15%
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+pub struct ValuationInputs {
+    /// EPS without non-recurring items
+    pub eps: Option<f64>,
+    pub book_value_per_share: Option<f64>,
+    pub tangible_book_per_share: Option<f64>,
+    pub fcf_per_share: Option<f64>,
+    /// Average free cash flow per share of all fiscal years
+    pub average_fcf_per_share: Option<f64>,
+    pub revenue_per_share: Option<f64>,
+    /// Shares outstanding at the end of the period
+    pub shares: Option<f64>,
+    pub revenue: Option<f64>,
+    pub cash: Option<f64>,
+    pub accounts_receivable: Option<f64>,
+    pub inventories: Option<f64>,
+    pub current_assets: Option<f64>,
+    pub total_liabilities: Option<f64>,
+    /// Short-term and long-term debt including capital lease obligations
+    pub total_debt: Option<f64>,
+    /// Average operating margin of all fiscal years
+    pub average_operating_margin: Option<f64>,
+    /// Average tax rate of all fiscal years
+    pub average_tax_rate: Option<f64>,
+    /// Median P/E of all fiscal years
+    pub median_pe: Option<f64>,
+    /// Median P/S of all fiscal years
+    pub median_ps: Option<f64>,
+    /// Median P/B of all fiscal years
+    pub median_pb: Option<f64>,
+    /// Median price to free cash flow of all fiscal years
+    pub median_pfcf: Option<f64>,
+    /// Expected growth of earnings, i.e. the analysts' long-term growth rate, or else the
+    /// past growth of EPS without NRI over 10, 5 or 3 years
+    pub earnings_growth: Option<f64>,
+    /// Expected growth of free cash flow, i.e. the past 3-year growth of free cash flow,
+    /// or else the expected growth of earnings
+    pub fcf_growth: Option<f64>,
+}
+
+impl ValuationInputs {
+    /// Collect the inputs from the annual financial data, the key ratios and (optionally)
+    /// the analyst estimates
+    pub fn from_data(
+        financials: &FinancialData,
+        key_ratios: &KeyRatios,
+        estimates: Option<&AnalystEstimates>,
+    ) -> ValuationInputs {
+        let data = &financials.financials;
+        let current = |section: &str, name: &str| series(data, section, name).and_then(current);
+        let per_share = |name: &str| current("per_share_data_array", name);
+        let percent = |value: f64| value / 100.0;
+        let growth = |name: &str| key_ratios.get("Growth", name).map(percent);
+
+        let total_debt = match (
+            current(
+                "balance_sheet",
+                "Short-Term Debt & Capital Lease Obligation",
+            ),
+            current("balance_sheet", "Long-Term Debt & Capital Lease Obligation"),
+        ) {
+            (None, None) => None,
+            (short, long) => Some(short.unwrap_or(0.0) + long.unwrap_or(0.0)),
+        };
+        let earnings_growth = estimates
+            .and_then(|estimates| estimates.annual.long_term_growth_rate_mean.as_f64())
+            .map(percent)
+            .or_else(|| growth("10-Year EPS without NRI Growth Rate"))
+            .or_else(|| growth("5-Year EPS without NRI Growth Rate"))
+            .or_else(|| growth("3-Year EPS without NRI Growth Rate"));
+        let annual = |section: &str, name: &str| {
+            series(data, section, name)
+                .map(|series| fiscal_year_values(&series))
+                .unwrap_or_default()
+        };
+        ValuationInputs {
+            eps: per_share("EPS without NRI"),
+            book_value_per_share: per_share("Book Value per Share"),
+            tangible_book_per_share: per_share("Tangible Book per Share"),
+            fcf_per_share: per_share("Free Cash Flow per Share"),
+            average_fcf_per_share: average(&annual(
+                "per_share_data_array",
+                "Free Cash Flow per Share",
+            )),
+            revenue_per_share: per_share("Revenue per Share"),
+            shares: current("valuation_and_quality", "Shares Outstanding (EOP)"),
+            revenue: current("income_statement", "Revenue"),
+            cash: current(
+                "balance_sheet",
+                "Cash, Cash Equivalents, Marketable Securities",
+            ),
+            accounts_receivable: current("balance_sheet", "Accounts Receivable"),
+            inventories: current("balance_sheet", "Total Inventories"),
+            current_assets: current("balance_sheet", "Total Current Assets"),
+            total_liabilities: current("balance_sheet", "Total Liabilities"),
+            total_debt,
+            average_operating_margin: average(&annual("common_size_ratios", "Operating Margin %"))
+                .map(percent),
+            average_tax_rate: average(&annual("income_statement", "Tax Rate %")).map(percent),
+            median_pe: median(annual("valuation_ratios", "PE Ratio")),
+            median_ps: median(annual("valuation_ratios", "PS Ratio")),
+            median_pb: median(annual("valuation_ratios", "PB Ratio")),
+            median_pfcf: median(annual("valuation_ratios", "Price-to-Free-Cash-Flow")),
+            earnings_growth,
+            fcf_growth: growth("3-Year FCF Growth Rate").or(earnings_growth),
+        }
+    }
+
+    /// Compute all intrinsic values per share with the given assumptions
+    pub fn values(&self, assumptions: &ValuationAssumptions) -> IntrinsicValues {
+        let expected_growth = assumptions.earnings_growth.or(self.earnings_growth);
+        let earnings_growth = expected_growth.map(|growth| assumptions.bounded(growth));
+        let fcf_growth = assumptions
+            .fcf_growth
+            .or(self.fcf_growth)
+            .map(|growth| assumptions.bounded(growth));
+        let tangible_book = if assumptions.add_tangible_book {
+            self.tangible_book_per_share.unwrap_or(0.0)
+        } else {
+            0.0
+        };
+        let eps = self.eps.filter(|eps| *eps > 0.0);
+        let fcf = self.fcf_per_share.filter(|fcf| *fcf > 0.0);
+        let per_share = |amount: Option<f64>| {
+            let shares = self.shares.filter(|shares| *shares > 0.0)?;
+            Some(amount? / shares)
+        };
+
+        let graham_number = match (eps, self.book_value_per_share) {
+            (Some(eps), Some(book)) if book > 0.0 => {
+                Some((assumptions.graham_multiplier * eps * book).sqrt())
+            }
+            _ => None,
+        };
+        let peter_lynch_value = match (eps, expected_growth) {
+            (Some(eps), Some(growth)) => {
+                let growth = growth.clamp(LYNCH_MIN_GROWTH, LYNCH_MAX_GROWTH);
+                Some(eps * growth * 100.0)
+            }
+            _ => None,
+        };
+        let net_current_asset_value =
+            per_share(difference(self.current_assets, self.total_liabilities));
+        let net_net_assets = self.cash.map(|cash| {
+            cash + 0.75 * self.accounts_receivable.unwrap_or(0.0)
+                + 0.5 * self.inventories.unwrap_or(0.0)
+        });
+        let net_net_working_capital = per_share(difference(net_net_assets, self.total_liabilities));
+        let earnings_power_value = match (
+            self.revenue,
+            self.average_operating_margin,
+            self.average_tax_rate,
+        ) {
+            (Some(revenue), Some(margin), Some(tax_rate)) => {
+                let earnings = revenue * margin * (1.0 - tax_rate);
+                let net_cash = self.cash.unwrap_or(0.0) - self.total_debt.unwrap_or(0.0);
+                per_share(Some(earnings / assumptions.discount_rate + net_cash))
+            }
+            _ => None,
+        };
+        let multiples = [
+            (self.median_pe, eps),
+            (self.median_ps, self.revenue_per_share),
+            (self.median_pb, self.book_value_per_share),
+            (self.median_pfcf, fcf),
+        ];
+        let gf_values: Vec<f64> = multiples
+            .iter()
+            .filter_map(|(multiple, value)| Some(multiple.as_ref()? * value.as_ref()?))
+            .filter(|value| *value > 0.0)
+            .collect();
+
+        IntrinsicValues {
+            graham_number,
+            median_ps_value: self
+                .median_ps
+                .zip(self.revenue_per_share)
+                .map(|(ps, rps)| ps * rps),
+            peter_lynch_value,
+            net_current_asset_value,
+            net_net_working_capital,
+            projected_fcf: self
+                .average_fcf_per_share
+                .filter(|fcf| *fcf > 0.0)
+                .map(|fcf| assumptions.discounted_value(fcf, 0.0)),
+            tangible_book: self.tangible_book_per_share,
+            dcf_earnings_based: eps
+                .zip(earnings_growth)
+                .map(|(eps, growth)| assumptions.discounted_value(eps, growth) + tangible_book),
+            dcf_fcf_based: fcf
+                .zip(fcf_growth)
+                .map(|(fcf, growth)| assumptions.discounted_value(fcf, growth) + tangible_book),
+            gf_value: average(&gf_values),
+            earnings_power_value,
+        }
+    }
+}
+
+/// Intrinsic values per share as shown in `Chart`:
+/// * `graham_number`: square root of `graham_multiplier` times EPS times book value
+/// * `median_ps_value`: median P/S times revenue per share
+/// * `peter_lynch_value`: EPS times earnings growth in percent, bounded to 5 - 25
+/// * `net_current_asset_value`: current assets minus total liabilities
+/// * `net_net_working_capital`: cash plus 75% of receivables plus 50% of inventories
+///   minus total liabilities
+/// * `projected_fcf`: DCF of the average free cash flow without growth in the growth stage
+/// * `tangible_book`: tangible book value
+/// * `dcf_earnings_based`, `dcf_fcf_based`: DCF of EPS or free cash flow per share with the
+///   expected growth (plus tangible book, if configured)
+/// * `gf_value`: average of the median P/E, P/S, P/B and price to free cash flow applied to
+///   the current EPS, revenue, book value and free cash flow
+/// * `earnings_power_value`: revenue times average operating margin after average taxes,
+///   capitalized at the discount rate, plus cash minus debt
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+pub struct IntrinsicValues {
+    pub graham_number: Option<f64>,
+    pub median_ps_value: Option<f64>,
+    pub peter_lynch_value: Option<f64>,
+    pub net_current_asset_value: Option<f64>,
+    pub net_net_working_capital: Option<f64>,
+    pub projected_fcf: Option<f64>,
+    pub tangible_book: Option<f64>,
+    pub dcf_earnings_based: Option<f64>,
+    pub dcf_fcf_based: Option<f64>,
+    pub gf_value: Option<f64>,
+    pub earnings_power_value: Option<f64>,
+}
+
+impl From<&Chart> for IntrinsicValues {
+    /// Values reported by GuruFocus, e.g. to compare them with recomputed values
+    fn from(chart: &Chart) -> IntrinsicValues {
+        IntrinsicValues {
+            graham_number: chart.graham_number.as_f64(),
+            median_ps_value: chart.median_ps_value.as_f64(),
+            peter_lynch_value: chart.peter_lynch_value.as_f64(),
+            net_current_asset_value: chart.net_current_asset_value.as_f64(),
+            net_net_working_capital: chart.net_net_working_capital.as_f64(),
+            projected_fcf: chart.projected_fcf.as_f64(),
+            tangible_book: chart.tangible_book.as_f64(),
+            dcf_earnings_based: chart.dcf_earnings_based.as_f64(),
+            dcf_fcf_based: chart.dcf_fcf_based.as_f64(),
+            gf_value: chart.gf_balue.as_f64(),
+            earnings_power_value: chart.earnings_power_value.as_f64(),
+        }
+    }
+}
+
+fn series(data: &DataPeriods, section: &str, name: &str) -> Option<FinancialSeries> {
+    data.annual_series(section, name).ok()
+}
+
+/// Value of the trailing twelve months, or else of the latest fiscal year
+fn current(series: FinancialSeries) -> Option<f64> {
+    series
+        .ttm()
+        .or_else(|| series.latest().map(|(_, value)| value))
+}
+
+fn fiscal_year_values(series: &FinancialSeries) -> Vec<f64> {
+    series
+        .without_ttm()
+        .entries()
+        .iter()
+        .filter_map(|(_, value, _)| *value)
+        .collect()
+}
+
+fn difference(x: Option<f64>, y: Option<f64>) -> Option<f64> {
+    Some(x? - y?)
+}
+
+fn average(values: &[f64]) -> Option<f64> {
+    if values.is_empty() {
+        None
+    } else {
+        Some(values.iter().sum::<f64>() / values.len() as f64)
+    }
+}
+
+fn median(mut values: Vec<f64>) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    values.sort_by(|a, b| a.total_cmp(b));
+    let mid = values.len() / 2;
+    if values.len().is_multiple_of(2) {
+        Some((values[mid - 1] + values[mid]) / 2.0)
+    } else {
+        Some(values[mid])
+    }
+}
+
+impl GuruFocusConnector {
+    /// Returns the inputs of the valuation models for the symbol given as argument, which
+    /// requires requesting the financials, key ratios and analyst estimates
+    pub async fn valuation_inputs(
+        &self,
+        stock: impl Into<Symbol>,
+    ) -> Result<ValuationInputs, GuruFocusError> {
+        let symbol = stock.into();
+        let (financials, key_ratios, estimates) = futures::future::try_join3(
+            self.financials(&symbol),
+            self.key_ratios(&symbol),
+            self.analyst_estimate(&symbol),
+        )
+        .await?;
+        Ok(ValuationInputs::from_data(
+            &financials,
+            &key_ratios,
+            Some(&estimates),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::replay::fixture_connector;
+    use crate::test_util::assert_close;
+
+    #[tokio::test]
+    async fn valuation_from_fixture() {
+        let gf_connect = fixture_connector();
+        let inputs = gf_connect.valuation_inputs("NAS:MSFT").await.unwrap();
+        assert_eq!(inputs.eps, Some(11.06));
+        assert_eq!(inputs.shares, Some(7432.0));
+        assert_eq!(inputs.total_debt, Some(8942.0 + 42688.0));
+        assert_close(inputs.earnings_growth.unwrap(), 0.1533, 1e-12);
+        assert_close(inputs.fcf_growth.unwrap(), 0.095, 1e-12);
+        assert_eq!(inputs.median_pe, Some(33.65));
+
+        let summary = gf_connect.stock_summary("NAS:MSFT").await.unwrap();
+        let chart = IntrinsicValues::from(&summary.summary.chart);
+        let assumptions = ValuationAssumptions::default();
+        let values = inputs.values(&assumptions);
+        assert_close(
+            values.graham_number.unwrap(),
+            chart.graham_number.unwrap(),
+            0.005,
+        );
+        assert_close(
+            values.net_current_asset_value.unwrap(),
+            chart.net_current_asset_value.unwrap(),
+            0.005,
+        );
+        assert_eq!(values.tangible_book, chart.tangible_book);
+        assert_close(values.peter_lynch_value.unwrap(), 11.06 * 15.33, 1e-9);
+        assert_close(
+            values.dcf_earnings_based.unwrap(),
+            assumptions.discounted_value(11.06, 0.1533) + 12.3,
+            1e-9,
+        );
+        assert_close(
+            values.gf_value.unwrap(),
+            (33.65 * 11.06 + 10.94 * 30.47 + 12.29 * 32.06 + 34.55 * 8.31) / 4.0,
+            1e-9,
+        );
+
+        // growth rates are bounded and the tangible book is optional
+        let scenario = ValuationAssumptions {
+            earnings_growth: Some(0.5),
+            add_tangible_book: false,
+            ..assumptions
+        };
+        assert_close(
+            inputs.values(&scenario).dcf_earnings_based.unwrap(),
+            assumptions.discounted_value(11.06, 0.2),
+            1e-9,
+        );
+        assert_eq!(
+            ValuationInputs::default().values(&assumptions),
+            IntrinsicValues::default()
+        );
+    }
+
+    #[test]
+    fn discounted_value() {
+        let assumptions = ValuationAssumptions {
+            discount_rate: 0.1,
+            growth_years: 2,
+            terminal_growth: 0.0,
+            terminal_years: 1,
+            ..ValuationAssumptions::default()
+        };
+        let expected = 1.1 / 1.1 + 1.21 / 1.21 + 1.21 / 1.331;
+        assert_close(assumptions.discounted_value(1.0, 0.1), expected, 1e-12);
+    }
+}